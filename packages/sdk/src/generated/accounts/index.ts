@@ -1,5 +1,7 @@
 export * from './FrequencyAccount';
+export * from './RuleSetStateAccount';
 
 import { FrequencyAccount } from './FrequencyAccount';
+import { RuleSetStateAccount } from './RuleSetStateAccount';
 
-export const accountProviders = { FrequencyAccount };
+export const accountProviders = { FrequencyAccount, RuleSetStateAccount };
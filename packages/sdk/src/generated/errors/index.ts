@@ -776,6 +776,751 @@ createErrorFromNameLookup.set(
   () => new ProgramOwnedSetCheckFailedError(),
 );
 
+/**
+ * TimeWindowCheckFailed: 'Time Window check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TimeWindowCheckFailedError extends Error {
+  readonly code: number = 0x24;
+  readonly name: string = 'TimeWindowCheckFailed';
+  constructor() {
+    super('Time Window check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, TimeWindowCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x24, () => new TimeWindowCheckFailedError());
+createErrorFromNameLookup.set('TimeWindowCheckFailed', () => new TimeWindowCheckFailedError());
+
+/**
+ * PubkeyFieldMatchCheckFailed: 'Pubkey Field Match check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class PubkeyFieldMatchCheckFailedError extends Error {
+  readonly code: number = 0x25;
+  readonly name: string = 'PubkeyFieldMatchCheckFailed';
+  constructor() {
+    super('Pubkey Field Match check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, PubkeyFieldMatchCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x25, () => new PubkeyFieldMatchCheckFailedError());
+createErrorFromNameLookup.set(
+  'PubkeyFieldMatchCheckFailed',
+  () => new PubkeyFieldMatchCheckFailedError(),
+);
+
+/**
+ * AmountFieldCheckFailed: 'Amount Field check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class AmountFieldCheckFailedError extends Error {
+  readonly code: number = 0x26;
+  readonly name: string = 'AmountFieldCheckFailed';
+  constructor() {
+    super('Amount Field check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, AmountFieldCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x26, () => new AmountFieldCheckFailedError());
+createErrorFromNameLookup.set('AmountFieldCheckFailed', () => new AmountFieldCheckFailedError());
+
+/**
+ * TokenAccountMatchCheckFailed: 'Token Account Match check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TokenAccountMatchCheckFailedError extends Error {
+  readonly code: number = 0x27;
+  readonly name: string = 'TokenAccountMatchCheckFailed';
+  constructor() {
+    super('Token Account Match check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, TokenAccountMatchCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x27, () => new TokenAccountMatchCheckFailedError());
+createErrorFromNameLookup.set(
+  'TokenAccountMatchCheckFailed',
+  () => new TokenAccountMatchCheckFailedError(),
+);
+
+/**
+ * TokenAccountAmountCheckFailed: 'Token Account Amount check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TokenAccountAmountCheckFailedError extends Error {
+  readonly code: number = 0x28;
+  readonly name: string = 'TokenAccountAmountCheckFailed';
+  constructor() {
+    super('Token Account Amount check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, TokenAccountAmountCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x28, () => new TokenAccountAmountCheckFailedError());
+createErrorFromNameLookup.set(
+  'TokenAccountAmountCheckFailed',
+  () => new TokenAccountAmountCheckFailedError(),
+);
+
+/**
+ * TokenGateCheckFailed: 'Token Gate check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TokenGateCheckFailedError extends Error {
+  readonly code: number = 0x29;
+  readonly name: string = 'TokenGateCheckFailed';
+  constructor() {
+    super('Token Gate check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, TokenGateCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x29, () => new TokenGateCheckFailedError());
+createErrorFromNameLookup.set('TokenGateCheckFailed', () => new TokenGateCheckFailedError());
+
+/**
+ * ThresholdCheckFailed: 'Threshold check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ThresholdCheckFailedError extends Error {
+  readonly code: number = 0x2a;
+  readonly name: string = 'ThresholdCheckFailed';
+  constructor() {
+    super('Threshold check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, ThresholdCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x2a, () => new ThresholdCheckFailedError());
+createErrorFromNameLookup.set('ThresholdCheckFailed', () => new ThresholdCheckFailedError());
+
+/**
+ * ThresholdUnreachable: 'Threshold requires more rules than it contains'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ThresholdUnreachableError extends Error {
+  readonly code: number = 0x2b;
+  readonly name: string = 'ThresholdUnreachable';
+  constructor() {
+    super('Threshold requires more rules than it contains');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, ThresholdUnreachableError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x2b, () => new ThresholdUnreachableError());
+createErrorFromNameLookup.set('ThresholdUnreachable', () => new ThresholdUnreachableError());
+
+/**
+ * MultiSignerCheckFailed: 'Multi Signer check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MultiSignerCheckFailedError extends Error {
+  readonly code: number = 0x2c;
+  readonly name: string = 'MultiSignerCheckFailed';
+  constructor() {
+    super('Multi Signer check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MultiSignerCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x2c, () => new MultiSignerCheckFailedError());
+createErrorFromNameLookup.set('MultiSignerCheckFailed', () => new MultiSignerCheckFailedError());
+
+/**
+ * MultiSignerUnreachable: 'Multi Signer requires more signers than it contains'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MultiSignerUnreachableError extends Error {
+  readonly code: number = 0x2d;
+  readonly name: string = 'MultiSignerUnreachable';
+  constructor() {
+    super('Multi Signer requires more signers than it contains');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MultiSignerUnreachableError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x2d, () => new MultiSignerUnreachableError());
+createErrorFromNameLookup.set('MultiSignerUnreachable', () => new MultiSignerUnreachableError());
+
+/**
+ * RuleSetRefSelf: 'RuleSet references itself'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class RuleSetRefSelfError extends Error {
+  readonly code: number = 0x2e;
+  readonly name: string = 'RuleSetRefSelf';
+  constructor() {
+    super('RuleSet references itself');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, RuleSetRefSelfError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x2e, () => new RuleSetRefSelfError());
+createErrorFromNameLookup.set('RuleSetRefSelf', () => new RuleSetRefSelfError());
+
+/**
+ * RuleSetRefTooDeep: 'RuleSet references are nested too deeply'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class RuleSetRefTooDeepError extends Error {
+  readonly code: number = 0x2f;
+  readonly name: string = 'RuleSetRefTooDeep';
+  constructor() {
+    super('RuleSet references are nested too deeply');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, RuleSetRefTooDeepError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x2f, () => new RuleSetRefTooDeepError());
+createErrorFromNameLookup.set('RuleSetRefTooDeep', () => new RuleSetRefTooDeepError());
+
+/**
+ * RuleRefNotFound: 'Rule definition not found'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class RuleRefNotFoundError extends Error {
+  readonly code: number = 0x30;
+  readonly name: string = 'RuleRefNotFound';
+  constructor() {
+    super('Rule definition not found');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, RuleRefNotFoundError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x30, () => new RuleRefNotFoundError());
+createErrorFromNameLookup.set('RuleRefNotFound', () => new RuleRefNotFoundError());
+
+/**
+ * RuleRefCycle: 'Rule definitions reference each other in a cycle'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class RuleRefCycleError extends Error {
+  readonly code: number = 0x31;
+  readonly name: string = 'RuleRefCycle';
+  constructor() {
+    super('Rule definitions reference each other in a cycle');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, RuleRefCycleError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x31, () => new RuleRefCycleError());
+createErrorFromNameLookup.set('RuleRefCycle', () => new RuleRefCycleError());
+
+/**
+ * TransactionIncludesProgramCheckFailed: 'Transaction Includes Program check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TransactionIncludesProgramCheckFailedError extends Error {
+  readonly code: number = 0x32;
+  readonly name: string = 'TransactionIncludesProgramCheckFailed';
+  constructor() {
+    super('Transaction Includes Program check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, TransactionIncludesProgramCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x32, () => new TransactionIncludesProgramCheckFailedError());
+createErrorFromNameLookup.set(
+  'TransactionIncludesProgramCheckFailed',
+  () => new TransactionIncludesProgramCheckFailedError(),
+);
+
+/**
+ * TopLevelProgramCheckFailed: 'Top Level Program check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TopLevelProgramCheckFailedError extends Error {
+  readonly code: number = 0x33;
+  readonly name: string = 'TopLevelProgramCheckFailed';
+  constructor() {
+    super('Top Level Program check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, TopLevelProgramCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x33, () => new TopLevelProgramCheckFailedError());
+createErrorFromNameLookup.set(
+  'TopLevelProgramCheckFailed',
+  () => new TopLevelProgramCheckFailedError(),
+);
+
+/**
+ * Ed25519PermitCheckFailed: 'Ed25519 Permit check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class Ed25519PermitCheckFailedError extends Error {
+  readonly code: number = 0x34;
+  readonly name: string = 'Ed25519PermitCheckFailed';
+  constructor() {
+    super('Ed25519 Permit check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, Ed25519PermitCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x34, () => new Ed25519PermitCheckFailedError());
+createErrorFromNameLookup.set(
+  'Ed25519PermitCheckFailed',
+  () => new Ed25519PermitCheckFailedError(),
+);
+
+/**
+ * MintMatchCheckFailed: 'Mint Match check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MintMatchCheckFailedError extends Error {
+  readonly code: number = 0x35;
+  readonly name: string = 'MintMatchCheckFailed';
+  constructor() {
+    super('Mint Match check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MintMatchCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x35, () => new MintMatchCheckFailedError());
+createErrorFromNameLookup.set('MintMatchCheckFailed', () => new MintMatchCheckFailedError());
+
+/**
+ * MintAccountMatchCheckFailed: 'Mint Account Match check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MintAccountMatchCheckFailedError extends Error {
+  readonly code: number = 0x36;
+  readonly name: string = 'MintAccountMatchCheckFailed';
+  constructor() {
+    super('Mint Account Match check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MintAccountMatchCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x36, () => new MintAccountMatchCheckFailedError());
+createErrorFromNameLookup.set(
+  'MintAccountMatchCheckFailed',
+  () => new MintAccountMatchCheckFailedError(),
+);
+
+/**
+ * MintAccountAmountCheckFailed: 'Mint Account Amount check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class MintAccountAmountCheckFailedError extends Error {
+  readonly code: number = 0x37;
+  readonly name: string = 'MintAccountAmountCheckFailed';
+  constructor() {
+    super('Mint Account Amount check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, MintAccountAmountCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x37, () => new MintAccountAmountCheckFailedError());
+createErrorFromNameLookup.set(
+  'MintAccountAmountCheckFailed',
+  () => new MintAccountAmountCheckFailedError(),
+);
+
+/**
+ * PubkeySortedListMatchCheckFailed: 'Pubkey Sorted List Match check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class PubkeySortedListMatchCheckFailedError extends Error {
+  readonly code: number = 0x38;
+  readonly name: string = 'PubkeySortedListMatchCheckFailed';
+  constructor() {
+    super('Pubkey Sorted List Match check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, PubkeySortedListMatchCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x38, () => new PubkeySortedListMatchCheckFailedError());
+createErrorFromNameLookup.set(
+  'PubkeySortedListMatchCheckFailed',
+  () => new PubkeySortedListMatchCheckFailedError(),
+);
+
+/**
+ * ProgramOwnedSortedListCheckFailed: 'Program Owned Sorted List check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ProgramOwnedSortedListCheckFailedError extends Error {
+  readonly code: number = 0x39;
+  readonly name: string = 'ProgramOwnedSortedListCheckFailed';
+  constructor() {
+    super('Program Owned Sorted List check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, ProgramOwnedSortedListCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x39, () => new ProgramOwnedSortedListCheckFailedError());
+createErrorFromNameLookup.set(
+  'ProgramOwnedSortedListCheckFailed',
+  () => new ProgramOwnedSortedListCheckFailedError(),
+);
+
+/**
+ * ListNotSorted: 'Sorted list is not sorted or contains duplicates'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ListNotSortedError extends Error {
+  readonly code: number = 0x3a;
+  readonly name: string = 'ListNotSorted';
+  constructor() {
+    super('Sorted list is not sorted or contains duplicates');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, ListNotSortedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x3a, () => new ListNotSortedError());
+createErrorFromNameLookup.set('ListNotSorted', () => new ListNotSortedError());
+
+/**
+ * PubkeyTreeExclusionCheckFailed: 'Pubkey Tree Exclusion check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class PubkeyTreeExclusionCheckFailedError extends Error {
+  readonly code: number = 0x3b;
+  readonly name: string = 'PubkeyTreeExclusionCheckFailed';
+  constructor() {
+    super('Pubkey Tree Exclusion check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, PubkeyTreeExclusionCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x3b, () => new PubkeyTreeExclusionCheckFailedError());
+createErrorFromNameLookup.set(
+  'PubkeyTreeExclusionCheckFailed',
+  () => new PubkeyTreeExclusionCheckFailedError(),
+);
+
+/**
+ * ProgramOwnedTreeExclusionCheckFailed: 'Program Owned Tree Exclusion check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ProgramOwnedTreeExclusionCheckFailedError extends Error {
+  readonly code: number = 0x3c;
+  readonly name: string = 'ProgramOwnedTreeExclusionCheckFailed';
+  constructor() {
+    super('Program Owned Tree Exclusion check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, ProgramOwnedTreeExclusionCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x3c, () => new ProgramOwnedTreeExclusionCheckFailedError());
+createErrorFromNameLookup.set(
+  'ProgramOwnedTreeExclusionCheckFailed',
+  () => new ProgramOwnedTreeExclusionCheckFailedError(),
+);
+
+/**
+ * QuotaCheckFailed: 'Quota check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class QuotaCheckFailedError extends Error {
+  readonly code: number = 0x3d;
+  readonly name: string = 'QuotaCheckFailed';
+  constructor() {
+    super('Quota check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, QuotaCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x3d, () => new QuotaCheckFailedError());
+createErrorFromNameLookup.set('QuotaCheckFailed', () => new QuotaCheckFailedError());
+
+/**
+ * StatefulRuleDuplicated: 'Stateful rules in an operation share their state'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class StatefulRuleDuplicatedError extends Error {
+  readonly code: number = 0x3e;
+  readonly name: string = 'StatefulRuleDuplicated';
+  constructor() {
+    super('Stateful rules in an operation share their state');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, StatefulRuleDuplicatedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x3e, () => new StatefulRuleDuplicatedError());
+createErrorFromNameLookup.set('StatefulRuleDuplicated', () => new StatefulRuleDuplicatedError());
+
+/**
+ * UsageLimitCheckFailed: 'Usage limit check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class UsageLimitCheckFailedError extends Error {
+  readonly code: number = 0x3f;
+  readonly name: string = 'UsageLimitCheckFailed';
+  constructor() {
+    super('Usage limit check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, UsageLimitCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x3f, () => new UsageLimitCheckFailedError());
+createErrorFromNameLookup.set('UsageLimitCheckFailed', () => new UsageLimitCheckFailedError());
+
+/**
+ * NonceCheckFailed: 'Nonce check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class NonceCheckFailedError extends Error {
+  readonly code: number = 0x40;
+  readonly name: string = 'NonceCheckFailed';
+  constructor() {
+    super('Nonce check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, NonceCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x40, () => new NonceCheckFailedError());
+createErrorFromNameLookup.set('NonceCheckFailed', () => new NonceCheckFailedError());
+
+/**
+ * AccountDataMatchCheckFailed: 'Account data match check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class AccountDataMatchCheckFailedError extends Error {
+  readonly code: number = 0x41;
+  readonly name: string = 'AccountDataMatchCheckFailed';
+  constructor() {
+    super('Account data match check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, AccountDataMatchCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x41, () => new AccountDataMatchCheckFailedError());
+createErrorFromNameLookup.set(
+  'AccountDataMatchCheckFailed',
+  () => new AccountDataMatchCheckFailedError(),
+);
+
+/**
+ * LamportsCheckFailed: 'Lamports check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class LamportsCheckFailedError extends Error {
+  readonly code: number = 0x42;
+  readonly name: string = 'LamportsCheckFailed';
+  constructor() {
+    super('Lamports check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, LamportsCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x42, () => new LamportsCheckFailedError());
+createErrorFromNameLookup.set('LamportsCheckFailed', () => new LamportsCheckFailedError());
+
+/**
+ * RentExemptCheckFailed: 'Rent exempt check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class RentExemptCheckFailedError extends Error {
+  readonly code: number = 0x43;
+  readonly name: string = 'RentExemptCheckFailed';
+  constructor() {
+    super('Rent exempt check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, RentExemptCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x43, () => new RentExemptCheckFailedError());
+createErrorFromNameLookup.set('RentExemptCheckFailed', () => new RentExemptCheckFailedError());
+
+/**
+ * IsProgramCheckFailed: 'Is program check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class IsProgramCheckFailedError extends Error {
+  readonly code: number = 0x44;
+  readonly name: string = 'IsProgramCheckFailed';
+  constructor() {
+    super('Is program check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, IsProgramCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x44, () => new IsProgramCheckFailedError());
+createErrorFromNameLookup.set('IsProgramCheckFailed', () => new IsProgramCheckFailedError());
+
+/**
+ * ProgramUpgradeAuthorityCheckFailed: 'Program upgrade authority check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class ProgramUpgradeAuthorityCheckFailedError extends Error {
+  readonly code: number = 0x45;
+  readonly name: string = 'ProgramUpgradeAuthorityCheckFailed';
+  constructor() {
+    super('Program upgrade authority check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, ProgramUpgradeAuthorityCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x45, () => new ProgramUpgradeAuthorityCheckFailedError());
+createErrorFromNameLookup.set(
+  'ProgramUpgradeAuthorityCheckFailed',
+  () => new ProgramUpgradeAuthorityCheckFailedError(),
+);
+
+/**
+ * AssociatedTokenAccountMatchCheckFailed: 'Associated token account match check failed'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class AssociatedTokenAccountMatchCheckFailedError extends Error {
+  readonly code: number = 0x46;
+  readonly name: string = 'AssociatedTokenAccountMatchCheckFailed';
+  constructor() {
+    super('Associated token account match check failed');
+    if (typeof Error.captureStackTrace === 'function') {
+      Error.captureStackTrace(this, AssociatedTokenAccountMatchCheckFailedError);
+    }
+  }
+}
+
+createErrorFromCodeLookup.set(0x46, () => new AssociatedTokenAccountMatchCheckFailedError());
+createErrorFromNameLookup.set(
+  'AssociatedTokenAccountMatchCheckFailed',
+  () => new AssociatedTokenAccountMatchCheckFailedError(),
+);
+
 /**
  * Attempts to resolve a custom program error from the provided error code.
  * @category Errors
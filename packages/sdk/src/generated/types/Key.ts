@@ -14,6 +14,7 @@ export enum Key {
   Uninitialized,
   RuleSet,
   Frequency,
+  RuleSetState,
 }
 
 /**
@@ -10,6 +10,7 @@ import * as beet from '@metaplex-foundation/beet';
 import * as beetSafecoin from '@metaplex-foundation/beet-solana';
 import { SeedsVec, seedsVecBeet } from './SeedsVec';
 import { ProofInfo, proofInfoBeet } from './ProofInfo';
+import { ExclusionProofInfo, exclusionProofInfoBeet } from './ExclusionProofInfo';
 /**
  * This type is used to derive the {@link PayloadType} type as well as the de/serializer.
  * However don't refer to it in your code but use the {@link PayloadType} type instead.
@@ -24,6 +25,7 @@ export type PayloadTypeRecord = {
   Seeds: { fields: [SeedsVec] };
   MerkleProof: { fields: [ProofInfo] };
   Number: { fields: [beet.bignum] };
+  ExclusionProof: { fields: [ExclusionProofInfo] };
 };
 
 /**
@@ -48,6 +50,9 @@ export const isPayloadTypeMerkleProof = (
 ): x is PayloadType & { __kind: 'MerkleProof' } => x.__kind === 'MerkleProof';
 export const isPayloadTypeNumber = (x: PayloadType): x is PayloadType & { __kind: 'Number' } =>
   x.__kind === 'Number';
+export const isPayloadTypeExclusionProof = (
+  x: PayloadType,
+): x is PayloadType & { __kind: 'ExclusionProof' } => x.__kind === 'ExclusionProof';
 
 /**
  * @category userTypes
@@ -82,4 +87,11 @@ export const payloadTypeBeet = beet.dataEnum<PayloadTypeRecord>([
       'PayloadTypeRecord["Number"]',
     ),
   ],
+  [
+    'ExclusionProof',
+    new beet.FixableBeetArgsStruct<PayloadTypeRecord['ExclusionProof']>(
+      [['fields', beet.tuple([exclusionProofInfoBeet])]],
+      'PayloadTypeRecord["ExclusionProof"]',
+    ),
+  ],
 ]) as beet.FixableBeet<PayloadType, PayloadType>;
@@ -1,4 +1,6 @@
+export * from './ConsumedNonce';
 export * from './CreateOrUpdateArgs';
+export * from './ExclusionProofInfo';
 export * from './Key';
 export * from './Payload';
 export * from './PayloadType';
@@ -6,6 +8,7 @@ export * from './ProofInfo';
 export * from './PuffRuleSetArgs';
 export * from './RuleSetHeader';
 export * from './RuleSetRevisionMapV1';
+export * from './RuleState';
 export * from './SeedsVec';
 export * from './ValidateArgs';
 export * from './WriteToBufferArgs';
@@ -153,6 +153,155 @@ pub enum RuleSetError {
     /// 35 - Program Owned Set check failed
     #[error("Program Owned Set check failed")]
     ProgramOwnedSetCheckFailed,
+
+    /// 36 - Time Window check failed
+    #[error("Time Window check failed")]
+    TimeWindowCheckFailed,
+
+    /// 37 - Pubkey Field Match check failed
+    #[error("Pubkey Field Match check failed")]
+    PubkeyFieldMatchCheckFailed,
+
+    /// 38 - Amount Field check failed
+    #[error("Amount Field check failed")]
+    AmountFieldCheckFailed,
+
+    /// 39 - Token Account Match check failed
+    #[error("Token Account Match check failed")]
+    TokenAccountMatchCheckFailed,
+
+    /// 40 - Token Account Amount check failed
+    #[error("Token Account Amount check failed")]
+    TokenAccountAmountCheckFailed,
+
+    /// 41 - Token Gate check failed
+    #[error("Token Gate check failed")]
+    TokenGateCheckFailed,
+
+    /// 42 - Threshold check failed
+    #[error("Threshold check failed")]
+    ThresholdCheckFailed,
+
+    /// 43 - Threshold requires more rules than it contains
+    #[error("Threshold requires more rules than it contains")]
+    ThresholdUnreachable,
+
+    /// 44 - Multi Signer check failed
+    #[error("Multi Signer check failed")]
+    MultiSignerCheckFailed,
+
+    /// 45 - Multi Signer requires more signers than it contains
+    #[error("Multi Signer requires more signers than it contains")]
+    MultiSignerUnreachable,
+
+    /// 46 - RuleSet references itself
+    #[error("RuleSet references itself")]
+    RuleSetRefSelf,
+
+    /// 47 - RuleSet references are nested too deeply
+    #[error("RuleSet references are nested too deeply")]
+    RuleSetRefTooDeep,
+
+    /// 48 - Rule definition not found
+    #[error("Rule definition not found")]
+    RuleRefNotFound,
+
+    /// 49 - Rule definitions reference each other in a cycle
+    #[error("Rule definitions reference each other in a cycle")]
+    RuleRefCycle,
+
+    /// 50 - Transaction Includes Program check failed
+    #[error("Transaction Includes Program check failed")]
+    TransactionIncludesProgramCheckFailed,
+
+    /// 51 - Top Level Program check failed
+    #[error("Top Level Program check failed")]
+    TopLevelProgramCheckFailed,
+
+    /// 52 - Ed25519 Permit check failed
+    #[error("Ed25519 Permit check failed")]
+    Ed25519PermitCheckFailed,
+
+    /// 53 - Mint Match check failed
+    #[error("Mint Match check failed")]
+    MintMatchCheckFailed,
+
+    /// 54 - Mint Account Match check failed
+    #[error("Mint Account Match check failed")]
+    MintAccountMatchCheckFailed,
+
+    /// 55 - Mint Account Amount check failed
+    #[error("Mint Account Amount check failed")]
+    MintAccountAmountCheckFailed,
+
+    /// 56 - Pubkey Sorted List Match check failed
+    #[error("Pubkey Sorted List Match check failed")]
+    PubkeySortedListMatchCheckFailed,
+
+    /// 57 - Program Owned Sorted List check failed
+    #[error("Program Owned Sorted List check failed")]
+    ProgramOwnedSortedListCheckFailed,
+
+    /// 58 - Sorted list is not sorted or contains duplicates
+    #[error("Sorted list is not sorted or contains duplicates")]
+    ListNotSorted,
+
+    /// 59 - Pubkey Tree Exclusion check failed
+    #[error("Pubkey Tree Exclusion check failed")]
+    PubkeyTreeExclusionCheckFailed,
+
+    /// 60 - Program Owned Tree Exclusion check failed
+    #[error("Program Owned Tree Exclusion check failed")]
+    ProgramOwnedTreeExclusionCheckFailed,
+
+    /// 61 - Quota check failed
+    #[error("Quota check failed")]
+    QuotaCheckFailed,
+
+    /// 62 - Stateful rules in an operation share their state
+    #[error("Stateful rules in an operation share their state")]
+    StatefulRuleDuplicated,
+
+    /// 63 - Usage limit check failed
+    #[error("Usage limit check failed")]
+    UsageLimitCheckFailed,
+
+    /// 64 - Nonce check failed
+    #[error("Nonce check failed")]
+    NonceCheckFailed,
+
+    /// 65 - Account data match check failed
+    #[error("Account data match check failed")]
+    AccountDataMatchCheckFailed,
+
+    /// 66 - Lamports check failed
+    #[error("Lamports check failed")]
+    LamportsCheckFailed,
+
+    /// 67 - Rent exempt check failed
+    #[error("Rent exempt check failed")]
+    RentExemptCheckFailed,
+
+    /// 68 - Is program check failed
+    #[error("Is program check failed")]
+    IsProgramCheckFailed,
+
+    /// 69 - Program upgrade authority check failed
+    #[error("Program upgrade authority check failed")]
+    ProgramUpgradeAuthorityCheckFailed,
+
+    /// 70 - Associated token account match check failed
+    #[error("Associated token account match check failed")]
+    AssociatedTokenAccountMatchCheckFailed,
+}
+
+/// The first `ProgramError::Custom` code used for owner-defined errors, so that they never
+/// collide with `RuleSetError` codes.
+pub const OWNER_ERROR_CODE_OFFSET: u32 = 0x10000;
+
+/// Convert an owner-defined error code into a `ProgramError` in the owner-defined range.
+pub fn owner_error(code: u16) -> ProgramError {
+    ProgramError::Custom(OWNER_ERROR_CODE_OFFSET + u32::from(code))
 }
 
 impl PrintProgramError for RuleSetError {
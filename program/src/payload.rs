@@ -35,6 +35,43 @@ impl ProofInfo {
     }
 }
 
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+/// A proof type used by the `PubkeyTreeExclusion` and `ProgramOwnedTreeExclusion` rules.  It
+/// proves that a `Pubkey` is not in a sorted Merkle tree by proving the two adjacent leaves it
+/// falls strictly between.
+pub struct ExclusionProofInfo {
+    /// The index of the left leaf.  The right leaf is at the next index.
+    pub index: u32,
+    /// The left leaf, which is less than the excluded `Pubkey`.
+    pub left: Pubkey,
+    /// The merkle proof of the left leaf.
+    pub left_proof: Vec<[u8; 32]>,
+    /// The right leaf, which is greater than the excluded `Pubkey`.
+    pub right: Pubkey,
+    /// The merkle proof of the right leaf.
+    pub right_proof: Vec<[u8; 32]>,
+}
+
+impl ExclusionProofInfo {
+    /// Create a new `ExclusionProofInfo`.
+    pub fn new(
+        index: u32,
+        left: Pubkey,
+        left_proof: Vec<[u8; 32]>,
+        right: Pubkey,
+        right_proof: Vec<[u8; 32]>,
+    ) -> Self {
+        Self {
+            index,
+            left,
+            left_proof,
+            right,
+            right_proof,
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 /// Variants representing the different types represented in a payload.
@@ -47,6 +84,8 @@ pub enum PayloadType {
     MerkleProof(ProofInfo),
     /// A plain `u64` used for `Amount`.
     Number(u64),
+    /// A merkle non-membership proof.
+    ExclusionProof(ExclusionProofInfo),
 }
 
 #[repr(C)]
@@ -141,6 +180,20 @@ impl Payload {
         }
     }
 
+    /// Get a reference to the `ExclusionProofInfo` associated with a key, if and only if the
+    /// `Payload` value is the `PayloadType::ExclusionProof` variant.  Returns `None` if the key is
+    /// not present in the `Payload` or the value is a different `PayloadType` variant.
+    pub fn get_exclusion_proof(&self, key: &String) -> Option<&ExclusionProofInfo> {
+        if let Some(val) = self.map.get(key) {
+            match val {
+                PayloadType::ExclusionProof(proof_info) => Some(proof_info),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
     /// Get the `u64` associated with a key, if and only if the `Payload` value is the
     /// `PayloadType::Number` variant.  Returns `None` if the key is not present in the `Payload`
     /// or the value is a different `PayloadType` variant.
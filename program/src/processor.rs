@@ -9,12 +9,13 @@ use crate::{
     },
     pda::{PREFIX, STATE_PDA},
     state::{
-        RuleSetHeader, RuleSetRevisionMapV1, RuleSetV1, CHUNK_SIZE, RULE_SET_LIB_VERSION,
-        RULE_SET_REV_MAP_VERSION, RULE_SET_SERIALIZED_HEADER_LEN,
+        RuleSetHeader, RuleSetRevisionMapV1, RuleSetStateAccount, RuleSetV2, ValidationContext,
+        CHUNK_SIZE, RULE_SET_LIB_VERSION, RULE_SET_REV_MAP_VERSION, RULE_SET_SERIALIZED_HEADER_LEN,
+        RULE_SET_V2_LIB_VERSION,
     },
     utils::{
         assert_derivation, create_or_allocate_account_raw, get_existing_revision_map,
-        get_operation, is_zeroed, resize_or_reallocate_account_raw,
+        get_existing_rule_set, get_operation, is_zeroed, resize_or_reallocate_account_raw,
     },
     MAX_NAME_LENGTH,
 };
@@ -26,6 +27,8 @@ use solana_program::{
     program_error::ProgramError,
     program_memory::{sol_memcmp, sol_memcpy},
     pubkey::{Pubkey, PUBKEY_BYTES},
+    rent::Rent,
+    sysvar::Sysvar,
 };
 
 /// The generic processor struct.
@@ -87,9 +90,9 @@ fn create_or_update_v1(
         return Err(RuleSetError::PayerIsNotSigner.into());
     }
 
-    // Deserialize `RuleSet`.
+    // Deserialize `RuleSet`.  A `RuleSetV1` deserializes as a `RuleSetV2` without definitions.
     let rule_set = match ctx.accounts.buffer_pda_info {
-        Some(account_info) => rmp_serde::from_slice::<RuleSetV1>(&account_info.data.borrow())
+        Some(account_info) => rmp_serde::from_slice::<RuleSetV2>(&account_info.data.borrow())
             .map_err(|_| RuleSetError::MessagePackDeserializationError)?,
         None => rmp_serde::from_slice(&serialized_rule_set)
             .map_err(|_| RuleSetError::MessagePackDeserializationError)?,
@@ -99,11 +102,29 @@ fn create_or_update_v1(
         return Err(RuleSetError::NameTooLong.into());
     }
 
-    // Make sure we know how to work with this RuleSet.
-    if rule_set.lib_version() != RULE_SET_LIB_VERSION {
-        return Err(RuleSetError::UnsupportedRuleSetVersion.into());
+    // Make sure we know how to work with this RuleSet.  Definitions are only stored by
+    // `RuleSetV2`, so a `RuleSetV1` carrying them is rejected rather than losing them.
+    match rule_set.lib_version() {
+        RULE_SET_LIB_VERSION if rule_set.definitions.is_empty() => (),
+        RULE_SET_V2_LIB_VERSION => (),
+        _ => return Err(RuleSetError::UnsupportedRuleSetVersion.into()),
     }
 
+    // Make sure every `Rule::Ref` can be resolved without cycles.
+    rule_set.check_definitions()?;
+
+    // Make sure every sorted list can be searched with a binary search.
+    rule_set.check_sorted_lists()?;
+
+    // Make sure every `Threshold` and `MultiSigner` can be met.
+    rule_set.check_thresholds()?;
+
+    // Make sure the `RuleSet` does not reference itself.
+    rule_set.check_rule_set_refs()?;
+
+    // Make sure every stateful `Rule` of an operation keeps its own state.
+    rule_set.check_rule_states()?;
+
     // The payer/signer must be the `RuleSet` owner.
     if ctx.accounts.payer_info.key != rule_set.owner() {
         return Err(RuleSetError::RuleSetOwnerMismatch.into());
@@ -205,6 +226,7 @@ fn create_or_update_v1(
                     .ok_or(RuleSetError::RuleSetRevisionNotAvailable)?,
                 &serialized_rev_map,
                 &account_info.data.borrow(),
+                rule_set.lib_version(),
             )?;
         }
         None => {
@@ -216,6 +238,7 @@ fn create_or_update_v1(
                     .ok_or(RuleSetError::RuleSetRevisionNotAvailable)?,
                 &serialized_rev_map,
                 &serialized_rule_set,
+                rule_set.lib_version(),
             )?;
         }
     };
@@ -237,7 +260,11 @@ fn validate<'a>(
 }
 
 /// V1 implementation of the `validate` instruction.
-fn validate_v1(program_id: &Pubkey, ctx: Context<Validate>, args: ValidateArgs) -> ProgramResult {
+fn validate_v1<'a>(
+    program_id: &Pubkey,
+    ctx: Context<'a, Validate<'a>>,
+    args: ValidateArgs,
+) -> ProgramResult {
     // Get the V1 arguments for the instruction.
     let ValidateArgs::V1 {
         operation,
@@ -258,102 +285,49 @@ fn validate_v1(program_id: &Pubkey, ctx: Context<Validate>, args: ValidateArgs)
         }
     }
 
-    // `RuleSet` must be owned by this program.
-    if *ctx.accounts.rule_set_pda_info.owner != crate::ID {
-        return Err(RuleSetError::IncorrectOwner.into());
-    }
-
-    // `RuleSet` must not be empty.
-    if ctx.accounts.rule_set_pda_info.data_is_empty() {
-        return Err(RuleSetError::DataIsEmpty.into());
-    }
-
-    // Get existing revision map and its serialized length.
-    let (revision_map, rev_map_location) =
-        get_existing_revision_map(ctx.accounts.rule_set_pda_info)?;
-
-    // Use the user-provided revision number to look up the `RuleSet` revision location in the PDA.
-    let (start, end) = match rule_set_revision {
-        Some(revision) => {
-            let start = revision_map
-                .rule_set_revisions
-                .get(revision)
-                .ok_or(RuleSetError::RuleSetRevisionNotAvailable)?;
-
-            let end_index = revision
-                .checked_add(1)
-                .ok_or(RuleSetError::NumericalOverflow)?;
-
-            let end = revision_map
-                .rule_set_revisions
-                .get(end_index)
-                .unwrap_or(&rev_map_location);
-            (*start, *end)
-        }
-        None => {
-            let start = revision_map
-                .rule_set_revisions
-                .last()
-                .ok_or(RuleSetError::RuleSetRevisionNotAvailable)?;
-            (*start, rev_map_location)
-        }
-    };
-
-    // Mutably borrow the existing `RuleSet` PDA data.
-    let data = ctx
-        .accounts
-        .rule_set_pda_info
-        .data
-        .try_borrow()
-        .map_err(|_| ProgramError::AccountBorrowFailed)?;
-
-    // Check `RuleSet` lib version.
-    let rule_set = match data.get(start) {
-        Some(&RULE_SET_LIB_VERSION) => {
-            // Increment starting location by size of lib version.
-            let start = start
-                .checked_add(1)
-                .ok_or(RuleSetError::NumericalOverflow)?;
-
-            // Deserialize `RuleSet`.
-            if end < ctx.accounts.rule_set_pda_info.data_len() {
-                rmp_serde::from_slice::<RuleSetV1>(&data[start..end])
-                    .map_err(|_| RuleSetError::MessagePackDeserializationError)?
-            } else {
-                return Err(RuleSetError::DataTypeMismatch.into());
-            }
-        }
-        Some(_) => return Err(RuleSetError::UnsupportedRuleSetVersion.into()),
-        None => return Err(RuleSetError::DataTypeMismatch.into()),
-    };
-
-    // Check `RuleSet` account info derivation.
-    let _bump = assert_derivation(
-        program_id,
-        ctx.accounts.rule_set_pda_info.key,
-        &[
-            PREFIX.as_bytes(),
-            rule_set.owner().as_ref(),
-            rule_set.name().as_bytes(),
-        ],
-    )?;
+    // Get the `RuleSet` revision, checking its owner and account info derivation.
+    let rule_set = get_existing_rule_set(ctx.accounts.rule_set_pda_info, rule_set_revision)?;
+
+    // If a `RuleSet` state PDA is provided, check account info derivation.  The state PDA is
+    // required if `RuleSet` state is to be updated.
+    if let Some(rule_set_state_pda_info) = ctx.accounts.rule_set_state_pda_info {
+        let bump = assert_derivation(
+            program_id,
+            rule_set_state_pda_info.key,
+            &[
+                STATE_PDA.as_bytes(),
+                rule_set.owner().as_ref(),
+                rule_set.name().as_bytes(),
+                ctx.accounts.mint_info.key.as_ref(),
+            ],
+        )?;
 
-    // If `RuleSet` state is to be updated, check account info derivation.
-    if update_rule_state {
-        if let Some(rule_set_state_pda_info) = ctx.accounts.rule_set_state_pda_info {
-            let _bump = assert_derivation(
-                program_id,
-                rule_set_state_pda_info.key,
-                &[
-                    STATE_PDA.as_bytes(),
-                    rule_set.owner().as_ref(),
-                    rule_set.name().as_bytes(),
-                    ctx.accounts.mint_info.key.as_ref(),
-                ],
+        // Create the `RuleSet` state PDA if state is to be updated and it does not exist yet.
+        if update_rule_state && rule_set_state_pda_info.data_is_empty() {
+            let rule_set_state_seeds = &[
+                STATE_PDA.as_bytes(),
+                rule_set.owner().as_ref(),
+                rule_set.name().as_bytes(),
+                ctx.accounts.mint_info.key.as_ref(),
+                &[bump],
+            ];
+
+            // Allocate an empty `RuleSetStateAccount`, which grows as the `Rule`s store state.
+            let state_account_len = RuleSetStateAccount::default().try_to_vec()?.len();
+
+            create_or_allocate_account_raw(
+                *program_id,
+                rule_set_state_pda_info,
+                ctx.accounts.system_program_info,
+                ctx.accounts
+                    .payer_info
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?,
+                state_account_len,
+                rule_set_state_seeds,
             )?;
-        } else {
-            return Err(ProgramError::NotEnoughAccountKeys);
         }
+    } else if update_rule_state {
+        return Err(ProgramError::NotEnoughAccountKeys);
     }
 
     // Convert remaining `Rule` accounts into a map of `Pubkey`s to the corresponding
@@ -366,20 +340,59 @@ fn validate_v1(program_id: &Pubkey, ctx: Context<Validate>, args: ValidateArgs)
         .collect::<HashMap<Pubkey, &AccountInfo>>();
 
     // Get the `Rule` from the `RuleSet` based on the user-specified operation.
-    let rule = get_operation(operation, &rule_set)?;
+    let rule = get_operation(operation.clone(), &rule_set)?;
 
     // Validate the `Rule`.
-    if let Err(err) = rule.validate(
-        &accounts_map,
-        &payload,
+    if let Err(err) = rule.validate(&ValidationContext {
+        accounts: &accounts_map,
+        payload: &payload,
         update_rule_state,
-        &ctx.accounts.rule_set_state_pda_info,
-        &ctx.accounts.rule_authority_info,
-    ) {
+        rule_set_state_pda: &ctx.accounts.rule_set_state_pda_info,
+        rule_authority: &ctx.accounts.rule_authority_info,
+        mint_info: ctx.accounts.mint_info,
+        definitions: &rule_set.definitions,
+        operation: &operation,
+        depth: 0,
+    }) {
         msg!("Failed to validate: {}", err);
         return Err(err);
     }
 
+    if update_rule_state {
+        if let (Some(rule_set_state_pda_info), Some(payer_info)) = (
+            ctx.accounts.rule_set_state_pda_info,
+            ctx.accounts.payer_info,
+        ) {
+            // Drop the state of the stateful `Rule`s that were removed from the latest revision of
+            // the `RuleSet`, so that it does not accumulate in the `RuleSet` state PDA.
+            if rule_set_revision.is_none() {
+                if let Some(state_keys) = rule_set.state_keys() {
+                    let mut state_account =
+                        RuleSetStateAccount::from_state_pda(rule_set_state_pda_info)?;
+                    let state_count = state_account.states.len();
+                    state_account
+                        .states
+                        .retain(|state_key, _| state_keys.contains(state_key));
+
+                    if state_account.states.len() != state_count {
+                        state_account.to_state_pda(rule_set_state_pda_info)?;
+                    }
+                }
+            }
+
+            // Pay the rent for any space the stateful `Rule`s added to the `RuleSet` state PDA.
+            let data_len = rule_set_state_pda_info.data_len();
+            if rule_set_state_pda_info.lamports() < Rent::get()?.minimum_balance(data_len) {
+                resize_or_reallocate_account_raw(
+                    rule_set_state_pda_info,
+                    payer_info,
+                    ctx.accounts.system_program_info,
+                    data_len,
+                )?;
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -573,6 +586,7 @@ fn write_data_to_pda(
     starting_location: usize,
     serialized_rev_map: &[u8],
     serialized_rule_set: &[u8],
+    lib_version: u8,
 ) -> ProgramResult {
     // Mutably borrow the `RuleSet` PDA data.
     let data = &mut rule_set_pda_info
@@ -586,7 +600,7 @@ fn write_data_to_pda(
         .checked_add(1)
         .ok_or(RuleSetError::NumericalOverflow)?;
     if end <= data.len() {
-        sol_memcpy(&mut data[start..end], &[RULE_SET_LIB_VERSION], 1);
+        sol_memcpy(&mut data[start..end], &[lib_version], 1);
     } else {
         return Err(RuleSetError::DataSliceUnexpectedIndexError.into());
     }
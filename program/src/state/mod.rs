@@ -1,8 +1,8 @@
 //! All structures and related functions representing a Rule Set on-chain.
 //!
 //! Key types include the main `RuleSetV1` type which keeps the the map of operations to `Rules`,
-//! as well as `RuleSetHeader` and `RuleSetRevisionMapV1` types used to manage data within the
-//! `RuleSet` PDA.
+//! the `RuleSetV2` type which adds a map of named `Rule` definitions, as well as `RuleSetHeader`
+//! and `RuleSetRevisionMapV1` types used to manage data within the `RuleSet` PDA.
 //!
 //! Each time a `RuleSet` is updated, a new revision is added to the PDA, and previous revisions
 //! never deleted.  The revision map is needed so that during `RuleSet` validation the desired
@@ -32,10 +32,12 @@ use solana_program::{
 
 mod frequency;
 mod rule_set;
+mod rule_set_state;
 mod rules;
 
 pub use frequency::*;
 pub use rule_set::*;
+pub use rule_set_state::*;
 pub use rules::*;
 
 use crate::{error::RuleSetError, utils::assert_owned_by};
@@ -54,6 +56,8 @@ pub enum Key {
     RuleSet,
     /// An account containing frequency state.
     Frequency,
+    /// An account containing the state of several stateful `Rule`s.
+    RuleSetState,
 }
 
 /// A trait implementing generic functions required by all accounts on Safecoin.
@@ -1,14 +1,14 @@
 /// See state module for description of PDA memory layout.
 use crate::{
     error::RuleSetError,
-    state::{Key, Rule},
+    state::{Key, Rule, RuleSetStateAccount, SignerSet},
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "serde-with-feature")]
 use serde_with::{As, DisplayFromStr};
 use solana_program::{entrypoint::ProgramResult, pubkey::Pubkey};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Version of the `RuleSetRevisionMapV1` struct.
 pub const RULE_SET_REV_MAP_VERSION: u8 = 1;
@@ -16,6 +16,9 @@ pub const RULE_SET_REV_MAP_VERSION: u8 = 1;
 /// Version of the `RuleSetV1` struct.
 pub const RULE_SET_LIB_VERSION: u8 = 1;
 
+/// Version of the `RuleSetV2` struct.
+pub const RULE_SET_V2_LIB_VERSION: u8 = 2;
+
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Debug, Clone)]
 /// Header used to keep track of where RuleSets are stored in the PDA.  This header is meant
 /// to be stored at the beginning of the PDA and never be versioned so that it always
@@ -49,11 +52,15 @@ pub struct RuleSetRevisionMapV1 {
     pub rule_set_revisions: Vec<usize>,
 }
 
+/// A `RuleSetV1` is a `RuleSetV2` without any definitions, which is serialized the same way.
+pub type RuleSetV1 = RuleSetV2;
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
-/// The struct containing all Rule Set data, most importantly the map of operations to `Rules`.
+/// The struct containing all Rule Set data, most importantly the map of operations to `Rules`
+/// and the map of names to reusable `Rule` definitions referenced by `Rule::Ref`.
 ///  See top-level module for description of PDA memory layout.
-pub struct RuleSetV1 {
+pub struct RuleSetV2 {
     /// Version of the RuleSet.  This is not a user version, but the version
     /// of this lib, to make sure that a `RuleSet` passed into our handlers
     /// is one we are compatible with.
@@ -65,16 +72,21 @@ pub struct RuleSetV1 {
     rule_set_name: String,
     /// A map to determine the `Rule` that belongs to a given `Operation`.
     pub operations: HashMap<String, Rule>,
+    /// A map to determine the `Rule` that belongs to a given definition name.  It is left out
+    /// of a `RuleSetV1`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub definitions: HashMap<String, Rule>,
 }
 
-impl RuleSetV1 {
-    /// Create a new empty `RuleSet`.
+impl RuleSetV2 {
+    /// Create a new empty `RuleSet`.  It is a `RuleSetV1` until a definition is added.
     pub fn new(rule_set_name: String, owner: Pubkey) -> Self {
         Self {
             lib_version: RULE_SET_LIB_VERSION,
             rule_set_name,
             owner,
             operations: HashMap::new(),
+            definitions: HashMap::new(),
         }
     }
 
@@ -108,4 +120,218 @@ impl RuleSetV1 {
     pub fn get(&self, operation: String) -> Option<&Rule> {
         self.operations.get(&operation)
     }
+
+    /// Add a named `Rule` definition into a `RuleSet`.  If this name is already in the `RuleSet`
+    /// nothing is updated and an error is returned.
+    pub fn add_definition(&mut self, name: String, rule: Rule) -> ProgramResult {
+        if self.definitions.get(&name).is_none() {
+            self.lib_version = RULE_SET_V2_LIB_VERSION;
+            self.definitions.insert(name, rule);
+            Ok(())
+        } else {
+            Err(RuleSetError::ValueOccupied.into())
+        }
+    }
+
+    /// Retrieve the `Rule` tree for a given definition name.
+    pub fn get_definition(&self, name: &str) -> Option<&Rule> {
+        self.definitions.get(name)
+    }
+
+    /// Check that every `Rule::Ref` names an existing definition and that no definition
+    /// references itself, either directly or through other definitions.
+    pub fn check_definitions(&self) -> ProgramResult {
+        let mut checked = HashSet::new();
+
+        for rule in self.operations.values().chain(self.definitions.values()) {
+            self.check_refs(rule, &mut vec![], &mut checked)?;
+        }
+
+        Ok(())
+    }
+
+    // Walk a `Rule` tree, following `Rule::Ref`s into their definitions.  `path` holds the
+    // definitions currently being walked and `checked` the definitions already found acyclic.
+    fn check_refs<'a>(
+        &'a self,
+        rule: &'a Rule,
+        path: &mut Vec<&'a str>,
+        checked: &mut HashSet<&'a str>,
+    ) -> ProgramResult {
+        if let Rule::Ref(name) = rule {
+            if path.contains(&name.as_str()) {
+                return Err(RuleSetError::RuleRefCycle.into());
+            }
+
+            if checked.contains(name.as_str()) {
+                return Ok(());
+            }
+
+            let definition = self
+                .get_definition(name)
+                .ok_or(RuleSetError::RuleRefNotFound)?;
+
+            path.push(name);
+            self.check_refs(definition, path, checked)?;
+            path.pop();
+
+            checked.insert(name);
+            return Ok(());
+        }
+
+        for child in rule.children() {
+            self.check_refs(child, path, checked)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that the list of every sorted list rule is sorted in ascending order without
+    /// duplicates, so that it can be searched with a binary search.
+    pub fn check_sorted_lists(&self) -> ProgramResult {
+        for rule in self.operations.values().chain(self.definitions.values()) {
+            check_sorted_lists(rule)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that every `Threshold` rule requires no more rules than it contains, and every
+    /// `MultiSigner` rule with a list of signers no more signers than the list contains, so that
+    /// they can be met.
+    pub fn check_thresholds(&self) -> ProgramResult {
+        for rule in self.operations.values().chain(self.definitions.values()) {
+            check_thresholds(rule)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that no `RuleSetRef` references this `RuleSet`, which would be followed until the
+    /// nesting limit is reached.
+    pub fn check_rule_set_refs(&self) -> ProgramResult {
+        for rule in self.operations.values().chain(self.definitions.values()) {
+            check_rule_set_refs(rule, self.owner(), self.name())?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that no two stateful `Rule`s evaluated for an operation store their state under the
+    /// same key, which would otherwise be updated once for each of them.
+    pub fn check_rule_states(&self) -> ProgramResult {
+        for (operation, rule) in &self.operations {
+            let mut state_keys = vec![];
+            self.collect_state_keys(rule, operation, &mut state_keys);
+
+            let mut found = HashSet::new();
+            if !state_keys.iter().all(|state_key| found.insert(state_key)) {
+                return Err(RuleSetError::StatefulRuleDuplicated.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the keys the stateful `Rule`s of every operation store their state under, or `None` if
+    /// a `RuleSetRef` is found, since the `Rule`s of the referenced `RuleSet` store their state in
+    /// the same `RuleSet` state PDA.
+    pub fn state_keys(&self) -> Option<HashSet<String>> {
+        let mut state_keys = vec![];
+        for (operation, rule) in &self.operations {
+            if !self.collect_state_keys(rule, operation, &mut state_keys) {
+                return None;
+            }
+        }
+
+        Some(state_keys.into_iter().collect())
+    }
+
+    // Walk a `Rule` tree, following `Rule::Ref`s into their definitions, and collect the keys of
+    // the stateful `Rule`s.  Returns `false` if a `RuleSetRef` is found.
+    fn collect_state_keys(
+        &self,
+        rule: &Rule,
+        operation: &str,
+        state_keys: &mut Vec<String>,
+    ) -> bool {
+        let mut complete = !matches!(rule, Rule::RuleSetRef { .. });
+
+        if let Some(state_key) = RuleSetStateAccount::state_key(rule, operation) {
+            state_keys.push(state_key);
+        }
+
+        if let Rule::Ref(name) = rule {
+            if let Some(definition) = self.get_definition(name) {
+                complete &= self.collect_state_keys(definition, operation, state_keys);
+            }
+        }
+
+        for child in rule.children() {
+            complete &= self.collect_state_keys(child, operation, state_keys);
+        }
+
+        complete
+    }
+}
+
+// Walk a `Rule` tree, checking the required count of every `Threshold` and `MultiSigner` rule.
+fn check_thresholds(rule: &Rule) -> ProgramResult {
+    match rule {
+        Rule::Threshold { required, rules } if *required as usize > rules.len() => {
+            return Err(RuleSetError::ThresholdUnreachable.into());
+        }
+        Rule::MultiSigner {
+            signers: SignerSet::List(pubkeys),
+            required,
+        } if *required as usize > pubkeys.iter().collect::<HashSet<_>>().len() => {
+            return Err(RuleSetError::MultiSignerUnreachable.into());
+        }
+        _ => (),
+    }
+
+    for child in rule.children() {
+        check_thresholds(child)?;
+    }
+
+    Ok(())
+}
+
+// Walk a `Rule` tree, looking for a `RuleSetRef` to the `RuleSet` with this owner and name.
+fn check_rule_set_refs(rule: &Rule, owner: &Pubkey, name: &str) -> ProgramResult {
+    match rule {
+        Rule::RuleSetRef {
+            owner: ref_owner,
+            name: ref_name,
+            ..
+        } if ref_owner == owner && ref_name == name => {
+            return Err(RuleSetError::RuleSetRefSelf.into());
+        }
+        _ => (),
+    }
+
+    for child in rule.children() {
+        check_rule_set_refs(child, owner, name)?;
+    }
+
+    Ok(())
+}
+
+// Walk a `Rule` tree, checking the list of every sorted list rule.
+fn check_sorted_lists(rule: &Rule) -> ProgramResult {
+    match rule {
+        Rule::PubkeySortedListMatch { pubkeys: list, .. }
+        | Rule::ProgramOwnedSortedList { programs: list, .. }
+            if !list.windows(2).all(|pair| pair[0] < pair[1]) =>
+        {
+            return Err(RuleSetError::ListNotSorted.into());
+        }
+        _ => (),
+    }
+
+    for child in rule.children() {
+        check_sorted_lists(child)?;
+    }
+
+    Ok(())
 }
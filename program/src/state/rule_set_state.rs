@@ -0,0 +1,122 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use shank::ShankAccount;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+};
+use std::collections::HashMap;
+
+use super::{Key, Rule, SafecoinAccount};
+use crate::utils::is_zeroed;
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Debug, Clone)]
+/// A nonce consumed by a `Nonce` rule.
+pub struct ConsumedNonce {
+    /// The nonce.
+    pub nonce: [u8; 32],
+    /// The Unix timestamp after which the nonce expires.
+    pub expiry: i64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Debug, Clone)]
+/// The state stored for a stateful `Rule`.
+pub enum RuleState {
+    /// The state of a `Quota` rule.
+    Quota {
+        /// The time the current period started.
+        period_start: i64,
+        /// The total amount moved during the current period.
+        amount: u64,
+    },
+    /// The state of a `Frequency` rule.
+    Frequency {
+        /// The last time the rule was validated with `update_rule_state` set.
+        last_update: i64,
+    },
+    /// The state of a `UsageLimit` rule.
+    UsageLimit {
+        /// The time the current period started.
+        period_start: i64,
+        /// The number of uses during the current period.
+        count: u64,
+    },
+    /// The state of a `Nonce` rule.
+    Nonce {
+        /// The nonces that have already been consumed and have not expired yet.
+        consumed: Vec<ConsumedNonce>,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Debug, Clone, ShankAccount)]
+/// An account containing the state of the stateful `Rule`s of a `RuleSet` for a mint.  Each
+/// `Rule` stores its state under its own key, so several stateful `Rule`s can share the account.
+pub struct RuleSetStateAccount {
+    /// The `Key` for this account which identifies it as a RuleSet State account.
+    pub key: Key,
+    /// The state of each stateful `Rule`, keyed by `RuleSetStateAccount::state_key`.
+    pub states: HashMap<String, RuleState>,
+}
+
+impl Default for RuleSetStateAccount {
+    fn default() -> Self {
+        Self {
+            key: Key::RuleSetState,
+            states: HashMap::new(),
+        }
+    }
+}
+
+impl RuleSetStateAccount {
+    /// Get the key a stateful `Rule` stores its state under, or `None` if the `Rule` keeps no
+    /// state.  The key does not depend on the parameters of the `Rule`, so the state is kept when
+    /// the `RuleSet` is updated.  `Quota` and `UsageLimit` state is keyed by the operation and the
+    /// name of the `Rule`, `Frequency` state by its authority, and the consumed nonces are shared
+    /// by every `Nonce` rule.
+    pub fn state_key(rule: &Rule, operation: &str) -> Option<String> {
+        match rule {
+            Rule::Frequency { authority, .. } => Some(format!("Frequency:{}", authority)),
+            Rule::Quota { name, .. } => Some(format!("Quota:{}:{}", operation, name)),
+            Rule::UsageLimit { name, .. } => Some(format!("UsageLimit:{}:{}", operation, name)),
+            Rule::Nonce { .. } => Some("Nonce".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Get the state from the `RuleSet` state PDA.  A PDA that has not been written to yet holds
+    /// no state.
+    pub fn from_state_pda(account: &AccountInfo) -> Result<Self, ProgramError> {
+        let is_initialized = {
+            let data = account
+                .data
+                .try_borrow()
+                .map_err(|_| ProgramError::AccountBorrowFailed)?;
+
+            !is_zeroed(&data)
+        };
+
+        if is_initialized {
+            Self::from_account_info(account)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// BorshSerialize the state into the `RuleSet` state PDA, reallocating it to fit.  The rent
+    /// for any added space is paid by the `Validate` payer after validation.
+    pub fn to_state_pda(&self, account: &AccountInfo) -> ProgramResult {
+        let data = self.try_to_vec()?;
+
+        if account.data_len() != data.len() {
+            account.realloc(data.len(), false)?;
+        }
+
+        account.try_borrow_mut_data()?.copy_from_slice(&data);
+
+        Ok(())
+    }
+}
+
+impl SafecoinAccount for RuleSetStateAccount {
+    fn key() -> Key {
+        Key::RuleSetState
+    }
+}
@@ -1,16 +1,34 @@
 use crate::{
-    error::RuleSetError,
-    payload::Payload,
-    // TODO: Uncomment this after on-curve sycall available.
-    // utils::is_on_curve,
-    utils::{assert_derivation, compute_merkle_root, is_zeroed},
+    error::{owner_error, RuleSetError},
+    payload::{Payload, PayloadType},
+    pda::find_rule_set_address,
+    state::{ConsumedNonce, RuleSetStateAccount, RuleState},
+    utils::{
+        assert_derivation, compute_merkle_root, get_existing_rule_set, get_operation,
+        get_permit_message, get_program_data_address, get_upgrade_authority, is_ed25519_signed,
+        is_excluded_from_tree, is_on_curve, is_program_data, is_zeroed, unpack_mint,
+        unpack_token_account,
+    },
 };
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "serde-with-feature")]
 use serde_with::{As, DisplayFromStr};
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
-    pubkey::Pubkey, system_program,
+    account_info::AccountInfo,
+    bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_option::COption,
+    pubkey,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar::{
+        instructions::{self, load_current_index_checked, load_instruction_at_checked},
+        Sysvar,
+    },
 };
 use std::collections::{HashMap, HashSet};
 
@@ -29,6 +47,258 @@ pub enum CompareOp {
     Gt,
 }
 
+impl CompareOp {
+    /// Compare two values using the operator, with `left` on the left-hand side.
+    pub fn compare<T: PartialOrd>(&self, left: &T, right: &T) -> bool {
+        match self {
+            CompareOp::Lt => left < right,
+            CompareOp::LtEq => left <= right,
+            CompareOp::Eq => left == right,
+            CompareOp::GtEq => left >= right,
+            CompareOp::Gt => left > right,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+/// Values from the Clock sysvar that can be used by a `TimeWindow` rule.
+pub enum ClockValue {
+    /// The current Unix timestamp.
+    UnixTimestamp,
+    /// The current slot.
+    Slot,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+/// The `Pubkey` fields of a token account that can be checked by a `TokenAccountMatch` rule.
+pub enum TokenAccountField {
+    /// The mint of the token account.
+    Mint,
+    /// The owner of the token account.
+    Owner,
+    /// The delegate of the token account.
+    Delegate,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+/// The `Pubkey` fields of a mint account that can be checked by a `MintAccountMatch` rule.
+pub enum MintAccountField {
+    /// The mint authority of the mint.
+    MintAuthority,
+    /// The freeze authority of the mint.
+    FreezeAuthority,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+/// The numerical fields of a mint account that can be checked by a `MintAccountAmount` rule.
+pub enum MintAmountField {
+    /// The total supply of the mint.
+    Supply,
+    /// The number of decimals of the mint.
+    Decimals,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+/// The checks an `AccountDataMatch` rule can run against the data of an account.
+pub enum AccountDataCheck {
+    /// The data at `offset` must start with `bytes`.
+    Bytes {
+        /// The offset into the account data.
+        offset: u64,
+        /// The expected bytes.
+        bytes: Vec<u8>,
+    },
+    /// The data length compared to `length` using `operator`.
+    Length {
+        /// The operator to be used in the comparison.
+        operator: CompareOp,
+        /// The length to be compared against.
+        length: u64,
+    },
+    /// The first 8 bytes of the data must be the discriminator, as used by Anchor accounts.
+    Discriminator([u8; 8]),
+}
+
+impl AccountDataCheck {
+    /// Whether the account data passes the check.  Data too short to hold the expected bytes
+    /// never passes.
+    pub fn check(&self, data: &[u8]) -> bool {
+        match self {
+            AccountDataCheck::Bytes { offset, bytes } => {
+                let start = match usize::try_from(*offset) {
+                    Ok(start) => start,
+                    Err(_) => return false,
+                };
+
+                match start.checked_add(bytes.len()) {
+                    Some(end) if end <= data.len() => data[start..end] == bytes[..],
+                    _ => false,
+                }
+            }
+            AccountDataCheck::Length { operator, length } => {
+                operator.compare(&(data.len() as u64), length)
+            }
+            AccountDataCheck::Discriminator(discriminator) => data.starts_with(discriminator),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+/// The `Pubkey`s a value can be compared against.
+pub enum PubkeyTarget {
+    /// A `Pubkey` stored in the rule.
+    Pubkey(
+        #[cfg_attr(feature = "serde-with-feature", serde(with = "As::<DisplayFromStr>"))] Pubkey,
+    ),
+    /// A list of `Pubkey`s stored in the rule, any of which can match.
+    List(Vec<Pubkey>),
+    /// A `Pubkey` located in the `Payload` by this field.
+    Field(String),
+    /// The mint account passed to `Validate`.
+    Mint,
+    /// A member of a Merkle tree.  The `Pubkey` and the proof located in the `Payload` by
+    /// `proof_field` are used to calculate a Merkle root, which is compared against the root.
+    Tree {
+        /// The root of the Merkle tree.
+        root: [u8; 32],
+        /// The field in the `Payload` to be compared
+        /// when looking for the Merkle proof.
+        proof_field: String,
+    },
+}
+
+impl PubkeyTarget {
+    /// Check whether a `Pubkey` matches the target.
+    pub fn matches(
+        &self,
+        key: &Pubkey,
+        payload: &Payload,
+        mint_info: &AccountInfo,
+    ) -> Result<bool, ProgramError> {
+        match self {
+            PubkeyTarget::Pubkey(pubkey) => Ok(key == pubkey),
+            PubkeyTarget::List(pubkeys) => Ok(pubkeys.contains(key)),
+            PubkeyTarget::Field(field) => match payload.get_pubkey(field) {
+                Some(pubkey) => Ok(key == pubkey),
+                None => Err(RuleSetError::MissingPayloadValue.into()),
+            },
+            PubkeyTarget::Mint => Ok(key == mint_info.key),
+            PubkeyTarget::Tree { root, proof_field } => {
+                match payload.get_merkle_proof(proof_field) {
+                    Some(merkle_proof) => Ok(compute_merkle_root(key, merkle_proof) == *root),
+                    None => Err(RuleSetError::MissingPayloadValue.into()),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+/// The amounts a value can be compared against.
+pub enum AmountTarget {
+    /// An amount stored in the rule.
+    Amount(u64),
+    /// An amount located in the `Payload` by this field.
+    Field(String),
+}
+
+impl AmountTarget {
+    /// Get the amount to compare against.
+    pub fn amount(&self, payload: &Payload) -> Result<u64, ProgramError> {
+        match self {
+            AmountTarget::Amount(amount) => Ok(*amount),
+            AmountTarget::Field(field) => payload
+                .get_amount(field)
+                .ok_or_else(|| RuleSetError::MissingPayloadValue.into()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+/// The set of `Pubkey`s whose signatures count towards a `MultiSigner` threshold.
+pub enum SignerSet {
+    /// A list of `Pubkey`s stored in the rule.
+    List(Vec<Pubkey>),
+    /// Members of a Merkle tree.  Each pair locates a signer `Pubkey` and its Merkle proof in
+    /// the `Payload`, which are used to calculate a Merkle root that is compared against the root.
+    Tree {
+        /// The root of the Merkle tree.
+        root: [u8; 32],
+        /// Pairs of fields in the `Payload` to be checked
+        /// when looking for each signer and its Merkle proof.
+        fields: Vec<(String, String)>,
+    },
+}
+
+impl SignerSet {
+    /// Get the distinct `Pubkey`s belonging to the set.
+    pub fn members(&self, payload: &Payload) -> Result<Vec<Pubkey>, ProgramError> {
+        let mut members: Vec<Pubkey> = vec![];
+        match self {
+            SignerSet::List(pubkeys) => {
+                for pubkey in pubkeys {
+                    if !members.contains(pubkey) {
+                        members.push(*pubkey);
+                    }
+                }
+            }
+            SignerSet::Tree { root, fields } => {
+                for (signer_field, proof_field) in fields {
+                    let key = payload
+                        .get_pubkey(signer_field)
+                        .ok_or(RuleSetError::MissingPayloadValue)?;
+
+                    let merkle_proof = payload
+                        .get_merkle_proof(proof_field)
+                        .ok_or(RuleSetError::MissingPayloadValue)?;
+
+                    // Signers that are not members of the tree do not count.
+                    if compute_merkle_root(key, merkle_proof) == *root && !members.contains(key) {
+                        members.push(*key);
+                    }
+                }
+            }
+        }
+
+        Ok(members)
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+/// A seed in a `PDATemplateMatch` derivation, either stored in the rule or built at validation.
+pub enum SeedTemplate {
+    /// Constant bytes stored in the rule.
+    Bytes(Vec<u8>),
+    /// The bytes of a `Pubkey` located in the `Payload` by this field.
+    Pubkey(String),
+    /// The little-endian bytes of an amount located in the `Payload` by this field.
+    Amount(String),
+    /// The bytes of the mint being validated.
+    Mint,
+}
+
+impl SeedTemplate {
+    /// Get the bytes of the seed.
+    pub fn to_bytes(
+        &self,
+        payload: &Payload,
+        mint_info: &AccountInfo,
+    ) -> Result<Vec<u8>, ProgramError> {
+        match self {
+            SeedTemplate::Bytes(bytes) => Ok(bytes.clone()),
+            SeedTemplate::Pubkey(field) => payload
+                .get_pubkey(field)
+                .map(|pubkey| pubkey.to_bytes().to_vec())
+                .ok_or_else(|| RuleSetError::MissingPayloadValue.into()),
+            SeedTemplate::Amount(field) => payload
+                .get_amount(field)
+                .map(|amount| amount.to_le_bytes().to_vec())
+                .ok_or_else(|| RuleSetError::MissingPayloadValue.into()),
+            SeedTemplate::Mint => Ok(mint_info.key.to_bytes().to_vec()),
+        }
+    }
+}
+
 /// Enum representation of Rule failure conditions
 pub enum RuleResult {
     /// The rule succeeded.
@@ -41,6 +311,36 @@ pub enum RuleResult {
 
 use RuleResult::*;
 
+/// The maximum number of `RuleSetRef`s that can be followed while validating a rule tree.
+pub const MAX_RULE_SET_REF_DEPTH: usize = 4;
+
+#[derive(Clone, Copy)]
+/// The accounts and arguments of a `Validate` call, shared by every `Rule` in a rule tree.
+pub struct ValidationContext<'a, 'info> {
+    /// The additional rule accounts, keyed by their `Pubkey`.
+    pub accounts: &'a HashMap<Pubkey, &'a AccountInfo<'info>>,
+    /// The `Payload` values the `Rule`s are checked against.
+    pub payload: &'a Payload,
+    /// Whether stateful `Rule`s update their state in the `RuleSet` state PDA.
+    pub update_rule_state: bool,
+    /// The `RuleSet` state PDA for the mint.
+    pub rule_set_state_pda: &'a Option<&'a AccountInfo<'info>>,
+    /// The authority that must sign for stateful `Rule`s.
+    pub rule_authority: &'a Option<&'a AccountInfo<'info>>,
+    /// The mint of the token being validated.
+    pub mint_info: &'a AccountInfo<'info>,
+    /// The named `Rule` definitions `Rule::Ref`s resolve to.
+    pub definitions: &'a HashMap<String, Rule>,
+    /// The operation being validated.
+    pub operation: &'a str,
+    /// The number of `RuleSetRef`s followed to reach the `Rule`.
+    pub depth: usize,
+}
+
+/// The associated token account program, whose addresses are derived by
+/// `AssociatedTokenAccountMatch`.
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("AToD9iqHSc2fhEP9Jp7UYA6mRjHQ4CTWyzCsw8X3tH7K");
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 /// The struct containing every type of Rule and its associated data.
 pub enum Rule {
@@ -173,14 +473,20 @@ pub enum Rule {
         /// The field the amount is stored in.
         field: String,
     },
-    /// Comparison based on time between operations.  Currently not implemented.  This rule
-    /// is planned check to ensure a certain amount of time has passed.  This rule will make use
-    /// of the `rule_set_state_pda` optional account passed into `Validate`, and will require
-    /// the optional `rule_authority` account to sign.
+    /// Comparison based on time between operations.  This rule checks that at least `period`
+    /// seconds have passed since the `last_update` time stored in the `RuleSetStateAccount` for
+    /// the mint, using the `unix_timestamp` from the Clock sysvar.  This rule makes use of the
+    /// `rule_set_state_pda` optional account passed into `Validate`, and requires the optional
+    /// `rule_authority` account to sign.  When `update_rule_state` is set, the `last_update` time
+    /// is set to the current time.
     Frequency {
         /// The authority of the frequency account.
         #[cfg_attr(feature = "serde-with-feature", serde(with = "As::<DisplayFromStr>"))]
         authority: Pubkey,
+        /// The period (in seconds) which must transpire before the rule will succeed again.
+        /// Defaults to zero for `Frequency` rules stored before it was added.
+        #[serde(default)]
+        period: i64,
     },
     /// The true test if a pubkey can be signed from a client and therefore is a true wallet account.
     /// The details of this rule are as follows: a wallet is defined as being both owned by the
@@ -209,25 +515,425 @@ pub enum Rule {
     },
     /// A rule that tells the operation finder to use the default namespace rule.
     Namespace,
+    /// The current time must be within a configured range.  The `clock_value` in the rule selects
+    /// whether the `unix_timestamp` or the `slot` from the Clock sysvar is used.  The range
+    /// includes `start` and excludes `end`, and either bound can be left open by setting it to
+    /// `None`.  When the `Validate` instruction is called, this rule does not require any
+    /// `Payload` values.
+    TimeWindow {
+        /// The start of the range.  If `None` then the range has no start.
+        start: Option<i64>,
+        /// The end of the range.  If `None` then the range has no end.
+        end: Option<i64>,
+        /// The Clock sysvar value to be compared.
+        clock_value: ClockValue,
+    },
+    /// Direct comparison between two `Pubkey`s in the `Payload`.  When the `Validate` instruction
+    /// is called, this rule requires two `PayloadType` values of `PayloadType::Pubkey`.  The
+    /// `field` and `other_field` values in the rule are used to locate the `Pubkey`s in the
+    /// payload, which must be equal.
+    PubkeyFieldMatch {
+        /// The field in the `Payload` to be compared.
+        field: String,
+        /// The field in the `Payload` to be compared against.
+        other_field: String,
+    },
+    /// Comparison between two amounts in the `Payload`.  When the `Validate` instruction is
+    /// called, this rule requires two `PayloadType` values of `PayloadType::Number`.  The `field`
+    /// and `other_field` values in the rule are used to locate the numerical amounts in the
+    /// payload, which are compared using the comparison operator stored in the rule, with the
+    /// `field` amount on the left-hand side.
+    AmountField {
+        /// The field the amount is stored in.
+        field: String,
+        /// The operator to be used in the comparison.
+        operator: CompareOp,
+        /// The field the amount to be compared against is stored in.
+        other_field: String,
+    },
+    /// A `Pubkey` field of a token account must match a target.  When the `Validate` instruction
+    /// is called, this rule requires a `PayloadType` value of `PayloadType::Pubkey`.  The `field`
+    /// value in the rule is used to locate the token account `Pubkey` in the payload.  Note this
+    /// same token account must also be provided to `Validate` via the `additional_rule_accounts`
+    /// argument so that it can be unpacked.  The `token_account_field` of the token account is
+    /// then compared against the `target` in the rule.  A token account with no delegate never
+    /// matches a `Delegate` check.
+    TokenAccountMatch {
+        /// The field in the `Payload` to be checked.
+        field: String,
+        /// The token account field to be compared.
+        token_account_field: TokenAccountField,
+        /// The target to be compared against.
+        target: PubkeyTarget,
+    },
+    /// Comparison against the balance of a token account.  When the `Validate` instruction is
+    /// called, this rule requires a `PayloadType` value of `PayloadType::Pubkey`.  The `field`
+    /// value in the rule is used to locate the token account `Pubkey` in the payload.  Note this
+    /// same token account must also be provided to `Validate` via the `additional_rule_accounts`
+    /// argument so that it can be unpacked.  The token account balance is compared to the
+    /// `target` amount using the comparison operator stored in the rule.
+    TokenAccountAmount {
+        /// The field in the `Payload` to be checked.
+        field: String,
+        /// The operator to be used in the comparison.
+        operator: CompareOp,
+        /// The amount to be compared against.
+        target: AmountTarget,
+    },
+    /// A wallet must hold a minimum balance of a given mint, or of any mint in a Merkle tree.
+    /// When the `Validate` instruction is called, this rule requires `PayloadType` values of
+    /// `PayloadType::Pubkey` for the wallet and its token account, as well as a
+    /// `PayloadType::MerkleProof` if the `mint` target is a Merkle tree.  The `field` values in
+    /// the rule are used to locate them in the `Payload`.  Note the token account must also be
+    /// provided to `Validate` via the `additional_rule_accounts` argument so that it can be
+    /// unpacked.  The token account must be owned by the wallet, its mint must match the `mint`
+    /// target, and its balance must be at least `amount`.
+    TokenGate {
+        /// The field in the `Payload` to be checked
+        /// when looking for the wallet.
+        wallet_field: String,
+        /// The field in the `Payload` to be checked
+        /// when looking for the token account.
+        token_account_field: String,
+        /// The mint(s) the token account must hold.
+        mint: PubkeyTarget,
+        /// The minimum balance the token account must hold.
+        amount: u64,
+    },
+    /// Group M-of-N, where at least `required` of the rules contained must pass.  A rule that
+    /// errors counts as not passing.  If too few rules pass, the result is a failure if any rule
+    /// failed, and otherwise an error, matching the behavior of `Any`.
+    Threshold {
+        /// The minimum number of Rules that must pass.
+        required: u32,
+        /// The vector of Rules contained under Threshold.
+        rules: Vec<Rule>,
+    },
+    /// At least `required` distinct members of a set of signers must be present.  When the
+    /// `Validate` instruction is called, each signer account must be provided to `Validate` via
+    /// the `additional_rule_accounts` argument so that whether it is a signer can be retrieved
+    /// from its `AccountInfo` struct.  Members that are not provided do not count as signers.
+    MultiSigner {
+        /// The signers that count towards the threshold.
+        signers: SignerSet,
+        /// The minimum number of signers that must have also signed the transaction.
+        required: u32,
+    },
+    /// Conditional, where the `then` rule must pass if the `condition` rule passes, and the
+    /// `else` rule must pass if the `condition` rule fails.  The `condition` rule is evaluated
+    /// only once, and an error from it is returned without evaluating either branch.
+    If {
+        /// The Rule deciding which branch is evaluated.
+        condition: Box<Rule>,
+        /// The Rule evaluated when the condition passes.
+        then: Box<Rule>,
+        /// The Rule evaluated when the condition fails.
+        r#else: Box<Rule>,
+    },
+    /// Always fails with an owner-defined error.  The code is returned offset by
+    /// `OWNER_ERROR_CODE_OFFSET` and the message is logged.
+    Deny {
+        /// The owner-defined error code.
+        code: u16,
+        /// A short message explaining the denial.
+        message: String,
+    },
+    /// Wrapper replacing the failure of the contained rule with an owner-defined error.  The code
+    /// is returned offset by `OWNER_ERROR_CODE_OFFSET` and the message is logged.  Errors from
+    /// the contained rule are returned as is.
+    WithError {
+        /// The Rule contained under WithError.
+        rule: Box<Rule>,
+        /// The owner-defined error code.
+        code: u16,
+        /// A short message explaining the failure.
+        message: String,
+    },
+    /// Reference to the rule for an operation in another `RuleSet`, which is evaluated as a
+    /// subtree.  When the `Validate` instruction is called, the referenced `RuleSet` account must
+    /// be provided to `Validate` via the `additional_rule_accounts` argument so that the
+    /// `RuleSet` can be deserialized from it.  Any state is kept in the `RuleSet` state PDA of
+    /// the `RuleSet` being validated.  At most `MAX_RULE_SET_REF_DEPTH` nested references are
+    /// followed.
+    RuleSetRef {
+        /// The owner of the referenced `RuleSet`.
+        #[cfg_attr(feature = "serde-with-feature", serde(with = "As::<DisplayFromStr>"))]
+        owner: Pubkey,
+        /// The name of the referenced `RuleSet`.
+        name: String,
+        /// The revision of the referenced `RuleSet` to use, or the latest revision if not
+        /// specified.
+        revision: Option<usize>,
+        /// The operation in the referenced `RuleSet` whose rule is evaluated.
+        operation: String,
+    },
+    /// Reference to a named `Rule` definition in the `RuleSet`, which is evaluated in place of
+    /// this rule.  Only available in `RuleSetV2`.
+    Ref(String),
+    /// A top-level instruction in the transaction must invoke a program matching the target.
+    /// When the `Validate` instruction is called, the Instructions sysvar account must be
+    /// provided to `Validate` via the `additional_rule_accounts` argument so that the
+    /// transaction's instructions can be inspected.  Use `Not` to forbid a program.
+    TransactionIncludesProgram {
+        /// The program(s) a top-level instruction must invoke.
+        program: PubkeyTarget,
+    },
+    /// The currently executing top-level instruction, which invokes this program either directly
+    /// or through CPI, must invoke a program matching the target.  When the `Validate`
+    /// instruction is called, the Instructions sysvar account must be provided to `Validate` via
+    /// the `additional_rule_accounts` argument so that the current instruction can be inspected.
+    TopLevelProgram {
+        /// The program(s) the current top-level instruction must invoke.
+        program: PubkeyTarget,
+    },
+    /// The transaction must include an Ed25519 program instruction verifying the authority's
+    /// signature over a permit message, so that the authority can approve an operation without
+    /// signing the transaction.  The message binds the mint, the operation, and the `Payload`
+    /// values of `fields` followed by `expiry_field`, as built by `get_permit_message`.  When the
+    /// `Validate` instruction is called, the Instructions sysvar account must be provided to
+    /// `Validate` via the `additional_rule_accounts` argument so that the Ed25519 program
+    /// instruction can be inspected.  A permit can be used until it expires.
+    Ed25519Permit {
+        /// The public key that must have signed the permit.
+        #[cfg_attr(feature = "serde-with-feature", serde(with = "As::<DisplayFromStr>"))]
+        authority: Pubkey,
+        /// The fields in the `Payload` whose values are bound by the permit.
+        fields: Vec<String>,
+        /// The field in the `Payload` holding the Unix timestamp after which the permit
+        /// expires, if any.
+        expiry_field: Option<String>,
+    },
+    /// A PDA derivation of seeds built from a template must match the `Pubkey` in the `Payload`.
+    /// Unlike `PDAMatch`, the seeds are fixed by the rule, mixing constant bytes with `Payload`
+    /// values and the mint being validated, so the rule constrains which PDA of the program is
+    /// allowed.  For example, an escrow PDA derived from `[b"escrow", mint]`.
+    PDATemplateMatch {
+        /// The program used for the PDA derivation.  If
+        /// `None` then the account owner is used.
+        program: Option<Pubkey>,
+        /// The field in the `Payload` to be compared
+        /// when looking for the PDA.
+        pda_field: String,
+        /// The template used to build the derivation seeds.
+        seeds: Vec<SeedTemplate>,
+    },
+    /// The mint passed to `Validate` must match a target.  This allows a `RuleSet` shared across
+    /// collections to carve out rules for specific mints.
+    MintMatch {
+        /// The mint(s) to be compared against.
+        mint: PubkeyTarget,
+    },
+    /// A `Pubkey` field of the mint passed to `Validate` must match a target.  The mint account is
+    /// unpacked and its `mint_account_field` is compared against the `target` in the rule.  A mint
+    /// with no such authority never matches.
+    MintAccountMatch {
+        /// The mint account field to be compared.
+        mint_account_field: MintAccountField,
+        /// The target to be compared against.
+        target: PubkeyTarget,
+    },
+    /// Comparison against a numerical field of the mint passed to `Validate`.  The mint account is
+    /// unpacked and its `mint_amount_field` is compared to the `target` amount using the
+    /// comparison operator stored in the rule.  For example, a non-fungible mint has zero decimals
+    /// and a supply of one.
+    MintAccountAmount {
+        /// The mint account field to be compared.
+        mint_amount_field: MintAmountField,
+        /// The operator to be used in the comparison.
+        operator: CompareOp,
+        /// The amount to be compared against.
+        target: AmountTarget,
+    },
+    /// The comparing `Pubkey` must be in the sorted list of `Pubkey`s.  This behaves like
+    /// `PubkeyListMatch`, but the list is searched with a binary search, so it must be sorted in
+    /// ascending order without duplicates.  This is checked when the `RuleSet` is created.
+    PubkeySortedListMatch {
+        /// The sorted list of public keys to be compared against.
+        pubkeys: Vec<Pubkey>,
+        /// The field in the `Payload` to be compared.
+        field: String,
+    },
+    /// The `Pubkey` must be owned by a program in the sorted list of `Pubkey`s.  This behaves
+    /// like `ProgramOwnedList`, but the list is searched with a binary search, so it must be
+    /// sorted in ascending order without duplicates.  This is checked when the `RuleSet` is
+    /// created.
+    ProgramOwnedSortedList {
+        /// The sorted list of programs, one of which must own the `Pubkey`.
+        programs: Vec<Pubkey>,
+        /// The field in the `Payload` to be compared.
+        field: String,
+    },
+    /// The comparing `Pubkey` must NOT be a member of the sorted Merkle tree in the rule.  When
+    /// the `Validate` instruction is called, this rule requires `PayloadType` values of
+    /// `PayloadType::Pubkey` and `PayloadType::ExclusionProof`.  The `field` values in the Rule
+    /// are used to locate them in the `Payload`.  The proof must show two adjacent leaves of the
+    /// tree that the `Pubkey` falls strictly between, as checked by `is_excluded_from_tree`.  The
+    /// leaves of the tree must be sorted and bounded by the all-zero and all-one `Pubkey`s, so
+    /// that every `Pubkey` not in the tree falls between two of them.
+    PubkeyTreeExclusion {
+        /// The root of the sorted Merkle tree.
+        root: [u8; 32],
+        /// The field in the `Payload` to be compared
+        /// when looking for the `Pubkey`.
+        pubkey_field: String,
+        /// The field in the `Payload` to be compared
+        /// when looking for the exclusion proof.
+        proof_field: String,
+    },
+    /// The `Pubkey` must NOT be owned by a member of the sorted Merkle tree in the rule.  This
+    /// behaves like `PubkeyTreeExclusion`, with the owner of the `Pubkey` account being checked.
+    /// Note this same `Pubkey` account must also be provided to `Validate` via the
+    /// `additional_rule_accounts` argument.  This is so that the `Pubkey`'s owner can be found
+    /// from its `AccountInfo` struct.
+    ProgramOwnedTreeExclusion {
+        /// The root of the sorted Merkle tree.
+        root: [u8; 32],
+        /// The field in the `Payload` to be compared
+        /// when looking for the `Pubkey`.
+        pubkey_field: String,
+        /// The field in the `Payload` to be compared
+        /// when looking for the exclusion proof.
+        proof_field: String,
+    },
+    /// The total amount moved within a period must not exceed a cap.  This rule adds the `Payload`
+    /// amount located by `field` to the total stored in the `RuleSetStateAccount` for the mint and
+    /// operation, and fails if the new total would exceed `cap`.  A period starts with the first
+    /// amount moved after the previous period has ended, at which point the total is reset.  This
+    /// rule makes use of the `rule_set_state_pda` optional account passed into `Validate`, and
+    /// requires the optional `rule_authority` account to sign.  The cap is only checked when
+    /// `update_rule_state` is set, since the amount would not be recorded otherwise.
+    Quota {
+        /// The name the total is stored under, which must be unique among the `Quota` rules of
+        /// an operation so that each keeps its own total.
+        name: String,
+        /// The authority of the quota account.
+        #[cfg_attr(feature = "serde-with-feature", serde(with = "As::<DisplayFromStr>"))]
+        authority: Pubkey,
+        /// The field the amount is stored in.
+        field: String,
+        /// The maximum total amount that can be moved within a period.
+        cap: u64,
+        /// The length of a period (in seconds).
+        period: i64,
+    },
+    /// The operation can only be validated `limit` times for the mint.  This rule counts the
+    /// successful validations of the operation in the `RuleSetStateAccount` for the mint, and
+    /// fails once `limit` uses have been counted.  If a `period` (in seconds) is given, the count
+    /// is reset with the first use after the period has ended.  This rule makes use of the
+    /// `rule_set_state_pda` optional account passed into `Validate`.  When `update_rule_state`
+    /// is set, the use is counted, which requires the optional `rule_authority` account to sign.
+    UsageLimit {
+        /// The name the count is stored under, which must be unique among the `UsageLimit` rules
+        /// of an operation so that each keeps its own count.
+        name: String,
+        /// The authority of the usage count.
+        #[cfg_attr(feature = "serde-with-feature", serde(with = "As::<DisplayFromStr>"))]
+        authority: Pubkey,
+        /// The maximum number of uses.
+        limit: u64,
+        /// The length of a period (in seconds) after which the count is reset, if any.
+        period: Option<i64>,
+    },
+    /// The nonce in the `Payload` must not have been consumed before for the mint.  The nonce
+    /// located by `field` is either a `Number` or a 32-byte hash passed as a `Pubkey`, and expires
+    /// at the Unix timestamp located by `expiry_field`, which must be at most `max_lifetime`
+    /// seconds away.  This rule makes use of the `rule_set_state_pda` optional account passed into
+    /// `Validate`, and requires `update_rule_state` to be set so the nonce is recorded as consumed,
+    /// which requires the optional `rule_authority` account to sign.  The consumed nonces are
+    /// shared by every `Nonce` rule for the mint, so they are kept when the `RuleSet` is updated.
+    /// Expired nonces are rejected, so they are pruned from the consumed nonces.  Combined with
+    /// `Ed25519Permit` binding both fields, this makes an approval single-use.
+    Nonce {
+        /// The authority of the consumed nonces.
+        #[cfg_attr(feature = "serde-with-feature", serde(with = "As::<DisplayFromStr>"))]
+        authority: Pubkey,
+        /// The field the nonce is stored in.
+        field: String,
+        /// The field the Unix timestamp after which the nonce expires is stored in.
+        expiry_field: String,
+        /// The maximum time (in seconds) a nonce can be used for before it expires.
+        max_lifetime: i64,
+    },
+    /// The data of an account must pass a check.  When the `Validate` instruction is called, this
+    /// rule requires a `PayloadType` value of `PayloadType::Pubkey`.  The `field` value in the
+    /// rule is used to locate the `Pubkey` in the payload.  Note this same `Pubkey` account must
+    /// also be provided to `Validate` via the `additional_rule_accounts` argument so that its data
+    /// can be read.  Combined with `ProgramOwned`, this allows only a specific account type of a
+    /// program, such as its escrow account.
+    AccountDataMatch {
+        /// The field in the `Payload` to be checked.
+        field: String,
+        /// The check to run against the account data.
+        check: AccountDataCheck,
+    },
+    /// Comparison against the lamports of an account.  When the `Validate` instruction is called,
+    /// this rule requires a `PayloadType` value of `PayloadType::Pubkey`.  The `field` value in
+    /// the rule is used to locate the `Pubkey` in the payload.  Note this same `Pubkey` account
+    /// must also be provided to `Validate` via the `additional_rule_accounts` argument so that its
+    /// lamports can be read.  The lamports are compared to the `target` using the `operator`.
+    Lamports {
+        /// The field in the `Payload` to be checked.
+        field: String,
+        /// The operator to be used in the comparison.
+        operator: CompareOp,
+        /// The amount to be compared against.
+        target: AmountTarget,
+    },
+    /// The account must hold enough lamports to be rent-exempt for the size of its data, using
+    /// the Rent sysvar.  When the `Validate` instruction is called, this rule requires a
+    /// `PayloadType` value of `PayloadType::Pubkey`.  The `field` value in the rule is used to
+    /// locate the `Pubkey` in the payload.  Note this same `Pubkey` account must also be provided
+    /// to `Validate` via the `additional_rule_accounts` argument.  An account with no lamports is
+    /// not rent-exempt.
+    RentExempt {
+        /// The field in the `Payload` to be checked.
+        field: String,
+    },
+    /// The `Pubkey` must be an executable program account.  When the `Validate` instruction is
+    /// called, this rule requires a `PayloadType` value of `PayloadType::Pubkey`.  The `field`
+    /// value in the rule is used to locate the `Pubkey` in the payload.  Note this same `Pubkey`
+    /// account must also be provided to `Validate` via the `additional_rule_accounts` argument so
+    /// that its `executable` flag can be read.  For a program of the upgradeable BPF loader, its
+    /// program data account must also be provided, and must not have been closed.
+    IsProgram {
+        /// The field in the `Payload` to be checked.
+        field: String,
+    },
+    /// The upgrade authority of a program must match a target, or the program must be immutable
+    /// if there is no target.  When the `Validate` instruction is called, this rule requires a
+    /// `PayloadType` value of `PayloadType::Pubkey`.  The `field` value in the rule is used to
+    /// locate the program `Pubkey` in the payload.  Note this same program account must also be
+    /// provided to `Validate` via the `additional_rule_accounts` argument.  For programs deployed
+    /// with the upgradeable BPF loader, the program data account must be provided as well, so
+    /// that the upgrade authority can be read.  Programs deployed with the other BPF loaders
+    /// cannot be upgraded and count as immutable.
+    ProgramUpgradeAuthority {
+        /// The field in the `Payload` to be checked.
+        field: String,
+        /// The upgrade authority to be compared against.  If `None` then the program must be
+        /// immutable.
+        authority: Option<PubkeyTarget>,
+    },
+    /// The `Pubkey` must be the associated token account of a wallet for the mint passed to
+    /// `Validate`.  When the `Validate` instruction is called, this rule requires `PayloadType`
+    /// values of `PayloadType::Pubkey` for both fields.  The `field` value in the rule is used to
+    /// locate the token account `Pubkey` and the `wallet_field` value the wallet `Pubkey` in the
+    /// payload.  The associated token account is derived using the owner of the mint as the token
+    /// program, so clients do not need to pass the derivation seeds.
+    AssociatedTokenAccountMatch {
+        /// The field in the `Payload` to be compared
+        /// when looking for the token account.
+        field: String,
+        /// The field in the `Payload` to be compared
+        /// when looking for the wallet.
+        wallet_field: String,
+    },
 }
 
 impl Rule {
     /// The top level validation function which parses an entire rule tree.
-    pub fn validate(
-        &self,
-        accounts: &HashMap<Pubkey, &AccountInfo>,
-        payload: &Payload,
-        update_rule_state: bool,
-        rule_set_state_pda: &Option<&AccountInfo>,
-        rule_authority: &Option<&AccountInfo>,
-    ) -> ProgramResult {
-        let result = self.low_level_validate(
-            accounts,
-            payload,
-            update_rule_state,
-            rule_set_state_pda,
-            rule_authority,
-        );
+    pub fn validate(&self, ctx: &ValidationContext) -> ProgramResult {
+        let result = self.low_level_validate(ctx);
 
         match result {
             Success(_) => Ok(()),
@@ -237,26 +943,25 @@ impl Rule {
     }
 
     /// Lower level validation function which iterates through a rule tree and applies boolean logic to rule results.
-    pub fn low_level_validate(
-        &self,
-        accounts: &HashMap<Pubkey, &AccountInfo>,
-        payload: &Payload,
-        _update_rule_state: bool,
-        _rule_set_state_pda: &Option<&AccountInfo>,
-        rule_authority: &Option<&AccountInfo>,
-    ) -> RuleResult {
+    pub fn low_level_validate(&self, ctx: &ValidationContext) -> RuleResult {
+        let ValidationContext {
+            accounts,
+            payload,
+            update_rule_state,
+            rule_set_state_pda,
+            rule_authority,
+            mint_info,
+            definitions,
+            operation,
+            depth,
+        } = *ctx;
+
         match self {
             Rule::All { rules } => {
                 msg!("Validating All");
                 let mut last: Option<ProgramError> = None;
                 for rule in rules {
-                    let result = rule.low_level_validate(
-                        accounts,
-                        payload,
-                        _update_rule_state,
-                        _rule_set_state_pda,
-                        rule_authority,
-                    );
+                    let result = rule.low_level_validate(ctx);
                     // Return failure on the first failing rule.
                     match result {
                         Success(err) => last = Some(err),
@@ -272,13 +977,7 @@ impl Rule {
                 let mut last_failure: Option<ProgramError> = None;
                 let mut last_error: Option<ProgramError> = None;
                 for rule in rules {
-                    let result = rule.low_level_validate(
-                        accounts,
-                        payload,
-                        _update_rule_state,
-                        _rule_set_state_pda,
-                        rule_authority,
-                    );
+                    let result = rule.low_level_validate(ctx);
                     match result {
                         Success(_) => return result,
                         Failure(err) => last_failure = Some(err),
@@ -297,13 +996,7 @@ impl Rule {
                 }
             }
             Rule::Not { rule } => {
-                let result = rule.low_level_validate(
-                    accounts,
-                    payload,
-                    _update_rule_state,
-                    _rule_set_state_pda,
-                    rule_authority,
-                );
+                let result = rule.low_level_validate(ctx);
 
                 // Negate the result.
                 match result {
@@ -354,13 +1047,7 @@ impl Rule {
                             .collect(),
                     };
 
-                    return new_rule.low_level_validate(
-                        accounts,
-                        payload,
-                        _update_rule_state,
-                        _rule_set_state_pda,
-                        rule_authority,
-                    );
+                    return new_rule.low_level_validate(ctx);
                 } else {
                     let key = match payload.get_pubkey(&field.to_owned()) {
                         Some(pubkey) => pubkey,
@@ -495,13 +1182,7 @@ impl Rule {
                             .collect(),
                     };
 
-                    return new_rule.low_level_validate(
-                        accounts,
-                        payload,
-                        _update_rule_state,
-                        _rule_set_state_pda,
-                        rule_authority,
-                    );
+                    return new_rule.low_level_validate(ctx);
                 } else {
                     let key = match payload.get_pubkey(&field.to_string()) {
                         Some(pubkey) => pubkey,
@@ -595,15 +1276,7 @@ impl Rule {
             } => {
                 msg!("Validating Amount");
                 if let Some(payload_amount) = &payload.get_amount(field) {
-                    let operator_fn = match operator {
-                        CompareOp::Lt => PartialOrd::lt,
-                        CompareOp::LtEq => PartialOrd::le,
-                        CompareOp::Eq => PartialEq::eq,
-                        CompareOp::Gt => PartialOrd::gt,
-                        CompareOp::GtEq => PartialOrd::ge,
-                    };
-
-                    if operator_fn(payload_amount, rule_amount) {
+                    if operator.compare(payload_amount, rule_amount) {
                         Success(self.to_error())
                     } else {
                         Failure(self.to_error())
@@ -612,7 +1285,7 @@ impl Rule {
                     Error(RuleSetError::MissingPayloadValue.into())
                 }
             }
-            Rule::Frequency { authority } => {
+            Rule::Frequency { authority, period } => {
                 msg!("Validating Frequency");
 
                 if let Some(rule_authority) = rule_authority {
@@ -625,7 +1298,56 @@ impl Rule {
                     return Error(RuleSetError::MissingAccount.into());
                 }
 
-                Error(RuleSetError::NotImplemented.into())
+                // The state PDA is needed to know when the last update happened.
+                let rule_set_state_pda = match rule_set_state_pda {
+                    Some(rule_set_state_pda) => rule_set_state_pda,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                // Get the current time from the Clock sysvar.
+                let now = match Clock::get() {
+                    Ok(clock) => clock.unix_timestamp,
+                    Err(err) => return Error(err),
+                };
+
+                let mut state_account =
+                    match RuleSetStateAccount::from_state_pda(rule_set_state_pda) {
+                        Ok(state_account) => state_account,
+                        Err(err) => return Error(err),
+                    };
+
+                let state_key = match RuleSetStateAccount::state_key(self, operation) {
+                    Some(state_key) => state_key,
+                    None => return Error(RuleSetError::UnexpectedRuleSetFailure.into()),
+                };
+
+                // If there is no previous state then this is the first update, otherwise the
+                // period must have transpired since the last update.
+                if let Some(RuleState::Frequency { last_update }) =
+                    state_account.states.get(&state_key)
+                {
+                    let next_update = match last_update.checked_add(*period) {
+                        Some(next_update) => next_update,
+                        None => return Error(RuleSetError::NumericalOverflow.into()),
+                    };
+
+                    if now < next_update {
+                        return Failure(self.to_error());
+                    }
+                }
+
+                // Store the current time as the last update.
+                if update_rule_state {
+                    state_account
+                        .states
+                        .insert(state_key, RuleState::Frequency { last_update: now });
+
+                    if let Err(err) = state_account.to_state_pda(rule_set_state_pda) {
+                        return Error(err);
+                    }
+                }
+
+                Success(self.to_error())
             }
             Rule::Pass => {
                 msg!("Validating Pass");
@@ -644,18 +1366,18 @@ impl Rule {
                 // its owner is the System Program.
                 if let Some(account) = accounts.get(key) {
                     if *account.owner != system_program::ID {
-                        // TODO: Change error return to commented line after on-curve syscall
-                        // available.
-                        return Error(RuleSetError::NotImplemented.into());
-                        //return (false, self.to_error());
+                        return Failure(self.to_error());
                     }
                 } else {
                     return Error(RuleSetError::MissingAccount.into());
                 }
 
-                // TODO: Uncomment call to `is_on_curve()` after on-curve sycall available.
-                Error(RuleSetError::NotImplemented.into())
-                //(is_on_curve(key), self.to_error())
+                // The `Pubkey` must be on-curve to be able to sign.
+                if is_on_curve(key) {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
             }
             Rule::ProgramOwnedSet { programs, field } => {
                 msg!("Validating ProgramOwnedSet");
@@ -673,13 +1395,7 @@ impl Rule {
                             .collect(),
                     };
 
-                    return new_rule.low_level_validate(
-                        accounts,
-                        payload,
-                        _update_rule_state,
-                        _rule_set_state_pda,
-                        rule_authority,
-                    );
+                    return new_rule.low_level_validate(ctx);
                 } else {
                     let key = match payload.get_pubkey(&field.to_string()) {
                         Some(pubkey) => pubkey,
@@ -717,22 +1433,1231 @@ impl Rule {
                 msg!("Validating Namespace");
                 Failure(self.to_error())
             }
-        }
-    }
+            Rule::TimeWindow {
+                start,
+                end,
+                clock_value,
+            } => {
+                msg!("Validating TimeWindow");
 
-    /// Convert the rule to a corresponding error resulting from the rule failure.
-    pub fn to_error(&self) -> ProgramError {
-        match self {
-            Rule::All { .. }
-            | Rule::Any { .. }
-            | Rule::Not { .. }
-            | Rule::Pass
-            | Rule::Namespace => RuleSetError::UnexpectedRuleSetFailure.into(),
-            Rule::AdditionalSigner { .. } => RuleSetError::AdditionalSignerCheckFailed.into(),
-            Rule::PubkeyMatch { .. } => RuleSetError::PubkeyMatchCheckFailed.into(),
+                let clock = match Clock::get() {
+                    Ok(clock) => clock,
+                    Err(err) => return Error(err),
+                };
+
+                // Get the current time from the Clock sysvar.
+                let now = match clock_value {
+                    ClockValue::UnixTimestamp => clock.unix_timestamp,
+                    ClockValue::Slot => match i64::try_from(clock.slot) {
+                        Ok(slot) => slot,
+                        Err(_) => return Error(RuleSetError::NumericalOverflow.into()),
+                    },
+                };
+
+                // Check the start and end bounds if they are set.
+                let after_start = match start {
+                    Some(start) => now >= *start,
+                    None => true,
+                };
+
+                let before_end = match end {
+                    Some(end) => now < *end,
+                    None => true,
+                };
+
+                if after_start && before_end {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::PubkeyFieldMatch { field, other_field } => {
+                msg!("Validating PubkeyFieldMatch");
+
+                let key = match payload.get_pubkey(field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let other_key = match payload.get_pubkey(other_field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                if key == other_key {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::AmountField {
+                field,
+                operator,
+                other_field,
+            } => {
+                msg!("Validating AmountField");
+
+                let amount = match payload.get_amount(field) {
+                    Some(amount) => amount,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let other_amount = match payload.get_amount(other_field) {
+                    Some(amount) => amount,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                if operator.compare(&amount, &other_amount) {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::TokenAccountMatch {
+                field,
+                token_account_field,
+                target,
+            } => {
+                msg!("Validating TokenAccountMatch");
+
+                let key = match payload.get_pubkey(field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let account = match accounts.get(key) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let token_account = match unpack_token_account(account) {
+                    Ok(token_account) => token_account,
+                    Err(err) => return Error(err),
+                };
+
+                // Get the `Pubkey` to compare from the token account.
+                let token_account_key = match token_account_field {
+                    TokenAccountField::Mint => token_account.mint,
+                    TokenAccountField::Owner => token_account.owner,
+                    TokenAccountField::Delegate => match token_account.delegate {
+                        COption::Some(delegate) => delegate,
+                        COption::None => return Failure(self.to_error()),
+                    },
+                };
+
+                match target.matches(&token_account_key, payload, mint_info) {
+                    Ok(true) => Success(self.to_error()),
+                    Ok(false) => Failure(self.to_error()),
+                    Err(err) => Error(err),
+                }
+            }
+            Rule::TokenAccountAmount {
+                field,
+                operator,
+                target,
+            } => {
+                msg!("Validating TokenAccountAmount");
+
+                let key = match payload.get_pubkey(field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let account = match accounts.get(key) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let token_account = match unpack_token_account(account) {
+                    Ok(token_account) => token_account,
+                    Err(err) => return Error(err),
+                };
+
+                let amount = match target.amount(payload) {
+                    Ok(amount) => amount,
+                    Err(err) => return Error(err),
+                };
+
+                if operator.compare(&token_account.amount, &amount) {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::TokenGate {
+                wallet_field,
+                token_account_field,
+                mint,
+                amount,
+            } => {
+                msg!("Validating TokenGate");
+
+                let wallet = match payload.get_pubkey(wallet_field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let key = match payload.get_pubkey(token_account_field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let account = match accounts.get(key) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let token_account = match unpack_token_account(account) {
+                    Ok(token_account) => token_account,
+                    Err(err) => return Error(err),
+                };
+
+                // The token account must be held by the wallet.
+                if token_account.owner != *wallet {
+                    return Failure(self.to_error());
+                }
+
+                // The token account must hold one of the gating mints.
+                match mint.matches(&token_account.mint, payload, mint_info) {
+                    Ok(true) => (),
+                    Ok(false) => return Failure(self.to_error()),
+                    Err(err) => return Error(err),
+                }
+
+                if token_account.amount >= *amount {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::Threshold { required, rules } => {
+                msg!("Validating Threshold");
+                let mut passed: u32 = 0;
+                let mut last_failure: Option<ProgramError> = None;
+                let mut last_error: Option<ProgramError> = None;
+
+                // A threshold of zero is trivially met.
+                if *required == 0 {
+                    return Success(self.to_error());
+                }
+
+                for rule in rules {
+                    let result = rule.low_level_validate(ctx);
+                    match result {
+                        Success(_) => {
+                            passed += 1;
+                            // Return pass as soon as enough rules passed.
+                            if passed >= *required {
+                                return Success(self.to_error());
+                            }
+                        }
+                        Failure(err) => last_failure = Some(err),
+                        Error(err) => last_error = Some(err),
+                    }
+                }
+
+                // Return failure if any rule failed, otherwise return the last invalid.
+                if last_failure.is_some() {
+                    Failure(self.to_error())
+                } else if let Some(err) = last_error {
+                    Error(err)
+                } else {
+                    // Fewer rules than required were provided.
+                    Failure(self.to_error())
+                }
+            }
+            Rule::MultiSigner { signers, required } => {
+                msg!("Validating MultiSigner");
+
+                let members = match signers.members(payload) {
+                    Ok(members) => members,
+                    Err(err) => return Error(err),
+                };
+
+                let signed = members
+                    .iter()
+                    .filter(|member| {
+                        matches!(accounts.get(*member), Some(account) if account.is_signer)
+                    })
+                    .count();
+
+                if signed >= *required as usize {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::If {
+                condition,
+                then,
+                r#else,
+            } => {
+                msg!("Validating If");
+                let result = condition.low_level_validate(ctx);
+
+                // Choose the branch based on the condition, or return the condition error.
+                let branch = match result {
+                    Success(_) => then,
+                    Failure(_) => r#else,
+                    Error(_) => return result,
+                };
+
+                branch.low_level_validate(ctx)
+            }
+            Rule::Deny { code, message } => {
+                msg!("Validating Deny");
+                msg!("Denied with code {}: {}", code, message);
+                Failure(self.to_error())
+            }
+            Rule::WithError {
+                rule,
+                code,
+                message,
+            } => {
+                msg!("Validating WithError");
+                let result = rule.low_level_validate(ctx);
+
+                // Replace the failure with the owner-defined error.
+                match result {
+                    Success(_) => Success(self.to_error()),
+                    Failure(_) => {
+                        msg!("Failed with code {}: {}", code, message);
+                        Failure(self.to_error())
+                    }
+                    Error(err) => Error(err),
+                }
+            }
+            Rule::RuleSetRef {
+                owner,
+                name,
+                revision,
+                operation: referenced_operation,
+            } => {
+                msg!("Validating RuleSetRef");
+
+                // Referenced `RuleSet`s can reference others in turn, possibly in a cycle, so
+                // only a limited number of them are followed.
+                if depth >= MAX_RULE_SET_REF_DEPTH {
+                    return Error(RuleSetError::RuleSetRefTooDeep.into());
+                }
+
+                let (key, _) = find_rule_set_address(*owner, name.to_string());
+
+                let account = match accounts.get(&key) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let rule_set = match get_existing_rule_set(account, *revision) {
+                    Ok(rule_set) => rule_set,
+                    Err(err) => return Error(err),
+                };
+
+                let rule = match get_operation(referenced_operation.to_string(), &rule_set) {
+                    Ok(rule) => rule,
+                    Err(err) => return Error(err),
+                };
+
+                // `Rule::Ref`s in the referenced rule resolve to the referenced definitions.
+                rule.low_level_validate(&ValidationContext {
+                    definitions: &rule_set.definitions,
+                    depth: depth + 1,
+                    ..*ctx
+                })
+            }
+            Rule::Ref(name) => {
+                msg!("Validating Ref");
+
+                let rule = match definitions.get(name) {
+                    Some(rule) => rule,
+                    None => return Error(RuleSetError::RuleRefNotFound.into()),
+                };
+
+                rule.low_level_validate(ctx)
+            }
+            Rule::TransactionIncludesProgram { program } => {
+                msg!("Validating TransactionIncludesProgram");
+
+                let instructions_info = match accounts.get(&instructions::ID) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                // Walk the top-level instructions until the index is out of range.
+                let mut index = 0;
+                while let Ok(instruction) = load_instruction_at_checked(index, instructions_info) {
+                    match program.matches(&instruction.program_id, payload, mint_info) {
+                        Ok(true) => return Success(self.to_error()),
+                        Ok(false) => (),
+                        Err(err) => return Error(err),
+                    }
+                    index += 1;
+                }
+
+                Failure(self.to_error())
+            }
+            Rule::TopLevelProgram { program } => {
+                msg!("Validating TopLevelProgram");
+
+                let instructions_info = match accounts.get(&instructions::ID) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let instruction = match load_current_index_checked(instructions_info)
+                    .and_then(|index| load_instruction_at_checked(index.into(), instructions_info))
+                {
+                    Ok(instruction) => instruction,
+                    Err(err) => return Error(err),
+                };
+
+                match program.matches(&instruction.program_id, payload, mint_info) {
+                    Ok(true) => Success(self.to_error()),
+                    Ok(false) => Failure(self.to_error()),
+                    Err(err) => Error(err),
+                }
+            }
+            Rule::Ed25519Permit {
+                authority,
+                fields,
+                expiry_field,
+            } => {
+                msg!("Validating Ed25519Permit");
+
+                let instructions_info = match accounts.get(&instructions::ID) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                // The permit must not have expired.
+                if let Some(expiry_field) = expiry_field {
+                    let expiry = match payload.get_amount(expiry_field) {
+                        Some(expiry) => expiry,
+                        _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                    };
+
+                    let now = match Clock::get() {
+                        Ok(clock) => clock.unix_timestamp,
+                        Err(err) => return Error(err),
+                    };
+
+                    if i128::from(now) > i128::from(expiry) {
+                        return Failure(self.to_error());
+                    }
+                }
+
+                let bound_fields = fields
+                    .iter()
+                    .chain(expiry_field.iter())
+                    .cloned()
+                    .collect::<Vec<String>>();
+
+                let message =
+                    match get_permit_message(mint_info.key, operation, payload, &bound_fields) {
+                        Ok(message) => message,
+                        Err(err) => return Error(err),
+                    };
+
+                // Walk the top-level instructions until the index is out of range.
+                let mut index = 0;
+                while let Ok(instruction) = load_instruction_at_checked(index, instructions_info) {
+                    if is_ed25519_signed(&instruction, authority, &message) {
+                        return Success(self.to_error());
+                    }
+                    index += 1;
+                }
+
+                Failure(self.to_error())
+            }
+            Rule::PDATemplateMatch {
+                program,
+                pda_field,
+                seeds,
+            } => {
+                msg!("Validating PDATemplateMatch");
+
+                // Get the PDA from the payload.
+                let account = match payload.get_pubkey(pda_field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                // Build the derivation seeds from the template.
+                let seeds = match seeds
+                    .iter()
+                    .map(|seed| seed.to_bytes(payload, mint_info))
+                    .collect::<Result<Vec<Vec<u8>>, ProgramError>>()
+                {
+                    Ok(seeds) => seeds,
+                    Err(err) => return Error(err),
+                };
+
+                // Get the program ID to use for the PDA derivation from the Rule.
+                let program = match program {
+                    // If the Pubkey is stored in the rule, use that value.
+                    Some(program) => program,
+                    None => {
+                        // If one is not stored, then assume the program ID is the account owner.
+                        match accounts.get(account) {
+                            Some(account) => account.owner,
+                            _ => return Error(RuleSetError::MissingAccount.into()),
+                        }
+                    }
+                };
+
+                // Convert the Vec of Vec into Vec of u8 slices.
+                let vec_of_slices = seeds.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>();
+
+                if let Ok(_bump) = assert_derivation(program, account, &vec_of_slices) {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::MintMatch { mint } => {
+                msg!("Validating MintMatch");
+
+                match mint.matches(mint_info.key, payload, mint_info) {
+                    Ok(true) => Success(self.to_error()),
+                    Ok(false) => Failure(self.to_error()),
+                    Err(err) => Error(err),
+                }
+            }
+            Rule::MintAccountMatch {
+                mint_account_field,
+                target,
+            } => {
+                msg!("Validating MintAccountMatch");
+
+                let mint = match unpack_mint(mint_info) {
+                    Ok(mint) => mint,
+                    Err(err) => return Error(err),
+                };
+
+                // Get the `Pubkey` to compare from the mint account.
+                let authority = match mint_account_field {
+                    MintAccountField::MintAuthority => mint.mint_authority,
+                    MintAccountField::FreezeAuthority => mint.freeze_authority,
+                };
+
+                let authority = match authority {
+                    COption::Some(authority) => authority,
+                    COption::None => return Failure(self.to_error()),
+                };
+
+                match target.matches(&authority, payload, mint_info) {
+                    Ok(true) => Success(self.to_error()),
+                    Ok(false) => Failure(self.to_error()),
+                    Err(err) => Error(err),
+                }
+            }
+            Rule::MintAccountAmount {
+                mint_amount_field,
+                operator,
+                target,
+            } => {
+                msg!("Validating MintAccountAmount");
+
+                let mint = match unpack_mint(mint_info) {
+                    Ok(mint) => mint,
+                    Err(err) => return Error(err),
+                };
+
+                // Get the amount to compare from the mint account.
+                let mint_amount = match mint_amount_field {
+                    MintAmountField::Supply => mint.supply,
+                    MintAmountField::Decimals => u64::from(mint.decimals),
+                };
+
+                let amount = match target.amount(payload) {
+                    Ok(amount) => amount,
+                    Err(err) => return Error(err),
+                };
+
+                if operator.compare(&mint_amount, &amount) {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::PubkeySortedListMatch { pubkeys, field } => {
+                msg!("Validating PubkeySortedListMatch");
+
+                let fields = field.split('|').collect::<Vec<&str>>();
+
+                if fields.len() > 1 {
+                    let new_rule = Rule::Any {
+                        rules: fields
+                            .iter()
+                            .map(|field| Rule::PubkeySortedListMatch {
+                                pubkeys: pubkeys.clone(),
+                                field: field.to_string(),
+                            })
+                            .collect(),
+                    };
+
+                    return new_rule.low_level_validate(ctx);
+                } else {
+                    let key = match payload.get_pubkey(&field.to_owned()) {
+                        Some(pubkey) => pubkey,
+                        _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                    };
+
+                    if pubkeys.binary_search(key).is_ok() {
+                        return Success(self.to_error());
+                    }
+                }
+
+                Failure(self.to_error())
+            }
+            Rule::ProgramOwnedSortedList { programs, field } => {
+                msg!("Validating ProgramOwnedSortedList");
+
+                let fields = field.split('|').collect::<Vec<&str>>();
+
+                if fields.len() > 1 {
+                    let new_rule = Rule::Any {
+                        rules: fields
+                            .iter()
+                            .map(|field| Rule::ProgramOwnedSortedList {
+                                programs: programs.clone(),
+                                field: field.to_string(),
+                            })
+                            .collect(),
+                    };
+
+                    return new_rule.low_level_validate(ctx);
+                } else {
+                    let key = match payload.get_pubkey(&field.to_string()) {
+                        Some(pubkey) => pubkey,
+                        _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                    };
+
+                    let account = match accounts.get(key) {
+                        Some(account) => account,
+                        _ => return Error(RuleSetError::MissingAccount.into()),
+                    };
+
+                    let data = match account.data.try_borrow() {
+                        Ok(data) => data,
+                        Err(_) => return Error(ProgramError::AccountBorrowFailed),
+                    };
+
+                    if is_zeroed(&data) {
+                        // Print helpful errors.
+                        if data.len() == 0 {
+                            msg!("Account data is empty");
+                        } else {
+                            msg!("Account data is zeroed");
+                        }
+
+                        return Error(RuleSetError::DataIsEmpty.into());
+                    } else if programs.binary_search(account.owner).is_ok() {
+                        // Account owner must be in the list.
+                        return Success(self.to_error());
+                    }
+                }
+
+                Failure(self.to_error())
+            }
+            Rule::PubkeyTreeExclusion {
+                root,
+                pubkey_field,
+                proof_field,
+            } => {
+                msg!("Validating PubkeyTreeExclusion");
+
+                // Get the `Pubkey` we are checking from the payload.
+                let key = match payload.get_pubkey(pubkey_field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                // Get the exclusion proof from the payload.
+                let proof_info = match payload.get_exclusion_proof(proof_field) {
+                    Some(proof_info) => proof_info,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                if is_excluded_from_tree(key, root, proof_info) {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::ProgramOwnedTreeExclusion {
+                root,
+                pubkey_field,
+                proof_field,
+            } => {
+                msg!("Validating ProgramOwnedTreeExclusion");
+
+                // Get the `Pubkey` we are checking from the payload.
+                let key = match payload.get_pubkey(pubkey_field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                // Get the `AccountInfo` struct for the `Pubkey`.
+                let account = match accounts.get(key) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let data = match account.data.try_borrow() {
+                    Ok(data) => data,
+                    Err(_) => return Error(ProgramError::AccountBorrowFailed),
+                };
+
+                // Account must have nonzero data to count as program-owned.
+                if is_zeroed(&data) {
+                    // Print helpful errors.
+                    if data.len() == 0 {
+                        msg!("Account data is empty");
+                    } else {
+                        msg!("Account data is zeroed");
+                    }
+
+                    return Error(RuleSetError::DataIsEmpty.into());
+                }
+
+                // Get the exclusion proof from the payload.
+                let proof_info = match payload.get_exclusion_proof(proof_field) {
+                    Some(proof_info) => proof_info,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                // The account owner must not be a leaf.
+                if is_excluded_from_tree(account.owner, root, proof_info) {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::Quota {
+                name: _,
+                authority,
+                field,
+                cap,
+                period,
+            } => {
+                msg!("Validating Quota");
+
+                // The amount is only recorded when state is being updated.
+                if !update_rule_state {
+                    msg!("Quota not checked without updating rule state");
+                    return Success(self.to_error());
+                }
+
+                if let Some(rule_authority) = rule_authority {
+                    if authority != rule_authority.key || !rule_authority.is_signer {
+                        return Error(RuleSetError::RuleAuthorityIsNotSigner.into());
+                    }
+                } else {
+                    return Error(RuleSetError::MissingAccount.into());
+                }
+
+                // The state PDA is needed to know the amount moved during the current period.
+                let rule_set_state_pda = match rule_set_state_pda {
+                    Some(rule_set_state_pda) => rule_set_state_pda,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let amount = match payload.get_amount(field) {
+                    Some(amount) => amount,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                // Get the current time from the Clock sysvar.
+                let now = match Clock::get() {
+                    Ok(clock) => clock.unix_timestamp,
+                    Err(err) => return Error(err),
+                };
+
+                let mut state_account =
+                    match RuleSetStateAccount::from_state_pda(rule_set_state_pda) {
+                        Ok(state_account) => state_account,
+                        Err(err) => return Error(err),
+                    };
+
+                let state_key = match RuleSetStateAccount::state_key(self, operation) {
+                    Some(state_key) => state_key,
+                    None => return Error(RuleSetError::UnexpectedRuleSetFailure.into()),
+                };
+
+                // If there is no previous state or the previous period has ended, then a new
+                // period starts now.
+                let (mut period_start, mut total) = (now, 0);
+                if let Some(RuleState::Quota {
+                    period_start: previous_period_start,
+                    amount: previous_total,
+                }) = state_account.states.get(&state_key)
+                {
+                    let period_end = match previous_period_start.checked_add(*period) {
+                        Some(period_end) => period_end,
+                        None => return Error(RuleSetError::NumericalOverflow.into()),
+                    };
+
+                    if now < period_end {
+                        (period_start, total) = (*previous_period_start, *previous_total);
+                    }
+                }
+
+                total = match total.checked_add(amount) {
+                    Some(total) => total,
+                    None => return Error(RuleSetError::NumericalOverflow.into()),
+                };
+
+                if total > *cap {
+                    return Failure(self.to_error());
+                }
+
+                // Store the new total for the period.
+                state_account.states.insert(
+                    state_key,
+                    RuleState::Quota {
+                        period_start,
+                        amount: total,
+                    },
+                );
+
+                if let Err(err) = state_account.to_state_pda(rule_set_state_pda) {
+                    return Error(err);
+                }
+
+                Success(self.to_error())
+            }
+            Rule::UsageLimit {
+                name: _,
+                authority,
+                limit,
+                period,
+            } => {
+                msg!("Validating UsageLimit");
+
+                // The state PDA is needed to know the number of previous uses.
+                let rule_set_state_pda = match rule_set_state_pda {
+                    Some(rule_set_state_pda) => rule_set_state_pda,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                // Get the current time from the Clock sysvar.
+                let now = match Clock::get() {
+                    Ok(clock) => clock.unix_timestamp,
+                    Err(err) => return Error(err),
+                };
+
+                let mut state_account =
+                    match RuleSetStateAccount::from_state_pda(rule_set_state_pda) {
+                        Ok(state_account) => state_account,
+                        Err(err) => return Error(err),
+                    };
+
+                let state_key = match RuleSetStateAccount::state_key(self, operation) {
+                    Some(state_key) => state_key,
+                    None => return Error(RuleSetError::UnexpectedRuleSetFailure.into()),
+                };
+
+                // If there is no previous state or the previous period has ended, then the count
+                // starts from zero.
+                let (mut period_start, mut count) = (now, 0);
+                if let Some(RuleState::UsageLimit {
+                    period_start: previous_period_start,
+                    count: previous_count,
+                }) = state_account.states.get(&state_key)
+                {
+                    let is_reset = match period {
+                        Some(period) => match previous_period_start.checked_add(*period) {
+                            Some(period_end) => now >= period_end,
+                            None => return Error(RuleSetError::NumericalOverflow.into()),
+                        },
+                        None => false,
+                    };
+
+                    if !is_reset {
+                        (period_start, count) = (*previous_period_start, *previous_count);
+                    }
+                }
+
+                if count >= *limit {
+                    return Failure(self.to_error());
+                }
+
+                if !update_rule_state {
+                    return Success(self.to_error());
+                }
+
+                if let Some(rule_authority) = rule_authority {
+                    if authority != rule_authority.key || !rule_authority.is_signer {
+                        return Error(RuleSetError::RuleAuthorityIsNotSigner.into());
+                    }
+                } else {
+                    return Error(RuleSetError::MissingAccount.into());
+                }
+
+                // Count this use.
+                state_account.states.insert(
+                    state_key,
+                    RuleState::UsageLimit {
+                        period_start,
+                        count: count + 1,
+                    },
+                );
+
+                if let Err(err) = state_account.to_state_pda(rule_set_state_pda) {
+                    return Error(err);
+                }
+
+                Success(self.to_error())
+            }
+            Rule::Nonce {
+                authority,
+                field,
+                expiry_field,
+                max_lifetime,
+            } => {
+                msg!("Validating Nonce");
+
+                // The nonce is only single-use if it is recorded as consumed.
+                if !update_rule_state {
+                    msg!("Nonce cannot be checked without updating rule state");
+                    return Error(self.to_error());
+                }
+
+                if let Some(rule_authority) = rule_authority {
+                    if authority != rule_authority.key || !rule_authority.is_signer {
+                        return Error(RuleSetError::RuleAuthorityIsNotSigner.into());
+                    }
+                } else {
+                    return Error(RuleSetError::MissingAccount.into());
+                }
+
+                // A `Number` nonce is stored in the first 8 bytes, little-endian.
+                let nonce = match payload.get(field) {
+                    Some(PayloadType::Pubkey(hash)) => hash.to_bytes(),
+                    Some(PayloadType::Number(number)) => {
+                        let mut nonce = [0u8; 32];
+                        nonce[..8].copy_from_slice(&number.to_le_bytes());
+                        nonce
+                    }
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let expiry = match payload.get_amount(expiry_field) {
+                    Some(expiry) => expiry,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                // Get the current time from the Clock sysvar.
+                let now = match Clock::get() {
+                    Ok(clock) => clock.unix_timestamp,
+                    Err(err) => return Error(err),
+                };
+
+                let latest_expiry = match now.checked_add(*max_lifetime) {
+                    Some(latest_expiry) => latest_expiry,
+                    None => return Error(RuleSetError::NumericalOverflow.into()),
+                };
+
+                // The nonce must not have expired, nor outlive the maximum lifetime.
+                let expiry = match i64::try_from(expiry) {
+                    Ok(expiry) if now <= expiry && expiry <= latest_expiry => expiry,
+                    _ => return Failure(self.to_error()),
+                };
+
+                // The state PDA is needed to know the nonces that have been consumed.
+                let rule_set_state_pda = match rule_set_state_pda {
+                    Some(rule_set_state_pda) => rule_set_state_pda,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let mut state_account =
+                    match RuleSetStateAccount::from_state_pda(rule_set_state_pda) {
+                        Ok(state_account) => state_account,
+                        Err(err) => return Error(err),
+                    };
+
+                let state_key = match RuleSetStateAccount::state_key(self, operation) {
+                    Some(state_key) => state_key,
+                    None => return Error(RuleSetError::UnexpectedRuleSetFailure.into()),
+                };
+
+                let mut consumed = match state_account.states.remove(&state_key) {
+                    Some(RuleState::Nonce { consumed }) => consumed,
+                    _ => vec![],
+                };
+
+                // Expired nonces can no longer be used, so they do not need to be kept.
+                consumed.retain(|consumed_nonce| consumed_nonce.expiry >= now);
+
+                if consumed
+                    .iter()
+                    .any(|consumed_nonce| consumed_nonce.nonce == nonce)
+                {
+                    return Failure(self.to_error());
+                }
+
+                // Consume the nonce.
+                consumed.push(ConsumedNonce { nonce, expiry });
+                state_account
+                    .states
+                    .insert(state_key, RuleState::Nonce { consumed });
+
+                if let Err(err) = state_account.to_state_pda(rule_set_state_pda) {
+                    return Error(err);
+                }
+
+                Success(self.to_error())
+            }
+            Rule::AccountDataMatch { field, check } => {
+                msg!("Validating AccountDataMatch");
+
+                let key = match payload.get_pubkey(field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let account = match accounts.get(key) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let data = match account.data.try_borrow() {
+                    Ok(data) => data,
+                    Err(_) => return Error(ProgramError::AccountBorrowFailed),
+                };
+
+                if check.check(&data) {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::Lamports {
+                field,
+                operator,
+                target,
+            } => {
+                msg!("Validating Lamports");
+
+                let key = match payload.get_pubkey(field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let account = match accounts.get(key) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let amount = match target.amount(payload) {
+                    Ok(amount) => amount,
+                    Err(err) => return Error(err),
+                };
+
+                if operator.compare(&account.lamports(), &amount) {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::RentExempt { field } => {
+                msg!("Validating RentExempt");
+
+                let key = match payload.get_pubkey(field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let account = match accounts.get(key) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                let rent = match Rent::get() {
+                    Ok(rent) => rent,
+                    Err(err) => return Error(err),
+                };
+
+                if account.lamports() > 0 && rent.is_exempt(account.lamports(), account.data_len())
+                {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::IsProgram { field } => {
+                msg!("Validating IsProgram");
+
+                let key = match payload.get_pubkey(field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let program_info = match accounts.get(key) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                if !program_info.executable {
+                    return Failure(self.to_error());
+                }
+
+                // A program of the upgradeable BPF loader stays executable when it is closed, so
+                // its program data account must be checked as well.
+                if *program_info.owner == bpf_loader_upgradeable::ID {
+                    let program_data_address = match get_program_data_address(program_info) {
+                        Ok(program_data_address) => program_data_address,
+                        Err(err) => return Error(err),
+                    };
+
+                    let program_data_info = match accounts.get(&program_data_address) {
+                        Some(account) => account,
+                        _ => return Error(RuleSetError::MissingAccount.into()),
+                    };
+
+                    match is_program_data(program_data_info) {
+                        Ok(true) => (),
+                        Ok(false) => return Failure(self.to_error()),
+                        Err(err) => return Error(err),
+                    }
+                }
+
+                Success(self.to_error())
+            }
+            Rule::ProgramUpgradeAuthority { field, authority } => {
+                msg!("Validating ProgramUpgradeAuthority");
+
+                let key = match payload.get_pubkey(field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let program_info = match accounts.get(key) {
+                    Some(account) => account,
+                    _ => return Error(RuleSetError::MissingAccount.into()),
+                };
+
+                if !program_info.executable {
+                    return Failure(self.to_error());
+                }
+
+                // Get the upgrade authority of the program.
+                let upgrade_authority = if *program_info.owner == bpf_loader_upgradeable::ID {
+                    let program_data_address = match get_program_data_address(program_info) {
+                        Ok(program_data_address) => program_data_address,
+                        Err(err) => return Error(err),
+                    };
+
+                    let program_data_info = match accounts.get(&program_data_address) {
+                        Some(account) => account,
+                        _ => return Error(RuleSetError::MissingAccount.into()),
+                    };
+
+                    // A closed program has no upgrade authority to match.
+                    match is_program_data(program_data_info) {
+                        Ok(true) => (),
+                        Ok(false) => return Failure(self.to_error()),
+                        Err(err) => return Error(err),
+                    }
+
+                    match get_upgrade_authority(program_data_info) {
+                        Ok(upgrade_authority) => upgrade_authority,
+                        Err(err) => return Error(err),
+                    }
+                } else if *program_info.owner == bpf_loader::ID
+                    || *program_info.owner == bpf_loader_deprecated::ID
+                {
+                    None
+                } else {
+                    return Failure(self.to_error());
+                };
+
+                let is_match = match (authority, upgrade_authority) {
+                    (Some(target), Some(upgrade_authority)) => {
+                        match target.matches(&upgrade_authority, payload, mint_info) {
+                            Ok(is_match) => is_match,
+                            Err(err) => return Error(err),
+                        }
+                    }
+                    (None, None) => true,
+                    _ => false,
+                };
+
+                if is_match {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+            Rule::AssociatedTokenAccountMatch {
+                field,
+                wallet_field,
+            } => {
+                msg!("Validating AssociatedTokenAccountMatch");
+
+                let token_account = match payload.get_pubkey(field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let wallet = match payload.get_pubkey(wallet_field) {
+                    Some(pubkey) => pubkey,
+                    _ => return Error(RuleSetError::MissingPayloadValue.into()),
+                };
+
+                let (associated_token_account, _) = Pubkey::find_program_address(
+                    &[
+                        wallet.as_ref(),
+                        mint_info.owner.as_ref(),
+                        mint_info.key.as_ref(),
+                    ],
+                    &ASSOCIATED_TOKEN_PROGRAM_ID,
+                );
+
+                if *token_account == associated_token_account {
+                    Success(self.to_error())
+                } else {
+                    Failure(self.to_error())
+                }
+            }
+        }
+    }
+
+    /// Get the `Rule`s directly contained by this rule.
+    pub fn children(&self) -> Vec<&Rule> {
+        match self {
+            Rule::All { rules } | Rule::Any { rules } | Rule::Threshold { rules, .. } => {
+                rules.iter().collect()
+            }
+            Rule::Not { rule } | Rule::WithError { rule, .. } => vec![rule],
+            Rule::If {
+                condition,
+                then,
+                r#else,
+            } => vec![condition, then, r#else],
+            _ => vec![],
+        }
+    }
+
+    /// Convert the rule to a corresponding error resulting from the rule failure.
+    pub fn to_error(&self) -> ProgramError {
+        match self {
+            Rule::All { .. }
+            | Rule::Any { .. }
+            | Rule::Not { .. }
+            | Rule::If { .. }
+            | Rule::RuleSetRef { .. }
+            | Rule::Ref(_)
+            | Rule::Pass
+            | Rule::Namespace => RuleSetError::UnexpectedRuleSetFailure.into(),
+            Rule::AdditionalSigner { .. } => RuleSetError::AdditionalSignerCheckFailed.into(),
+            Rule::PubkeyMatch { .. } => RuleSetError::PubkeyMatchCheckFailed.into(),
             Rule::PubkeyListMatch { .. } => RuleSetError::PubkeyListMatchCheckFailed.into(),
             Rule::PubkeyTreeMatch { .. } => RuleSetError::PubkeyTreeMatchCheckFailed.into(),
-            Rule::PDAMatch { .. } => RuleSetError::PDAMatchCheckFailed.into(),
+            Rule::PDAMatch { .. } | Rule::PDATemplateMatch { .. } => {
+                RuleSetError::PDAMatchCheckFailed.into()
+            }
             Rule::ProgramOwned { .. } => RuleSetError::ProgramOwnedCheckFailed.into(),
             Rule::ProgramOwnedList { .. } => RuleSetError::ProgramOwnedListCheckFailed.into(),
             Rule::ProgramOwnedTree { .. } => RuleSetError::ProgramOwnedTreeCheckFailed.into(),
@@ -740,6 +2665,46 @@ impl Rule {
             Rule::Frequency { .. } => RuleSetError::FrequencyCheckFailed.into(),
             Rule::IsWallet { .. } => RuleSetError::IsWalletCheckFailed.into(),
             Rule::ProgramOwnedSet { .. } => RuleSetError::ProgramOwnedSetCheckFailed.into(),
+            Rule::TimeWindow { .. } => RuleSetError::TimeWindowCheckFailed.into(),
+            Rule::PubkeyFieldMatch { .. } => RuleSetError::PubkeyFieldMatchCheckFailed.into(),
+            Rule::AmountField { .. } => RuleSetError::AmountFieldCheckFailed.into(),
+            Rule::TokenAccountMatch { .. } => RuleSetError::TokenAccountMatchCheckFailed.into(),
+            Rule::TokenAccountAmount { .. } => RuleSetError::TokenAccountAmountCheckFailed.into(),
+            Rule::TokenGate { .. } => RuleSetError::TokenGateCheckFailed.into(),
+            Rule::Threshold { .. } => RuleSetError::ThresholdCheckFailed.into(),
+            Rule::MultiSigner { .. } => RuleSetError::MultiSignerCheckFailed.into(),
+            Rule::TransactionIncludesProgram { .. } => {
+                RuleSetError::TransactionIncludesProgramCheckFailed.into()
+            }
+            Rule::TopLevelProgram { .. } => RuleSetError::TopLevelProgramCheckFailed.into(),
+            Rule::Ed25519Permit { .. } => RuleSetError::Ed25519PermitCheckFailed.into(),
+            Rule::MintMatch { .. } => RuleSetError::MintMatchCheckFailed.into(),
+            Rule::MintAccountMatch { .. } => RuleSetError::MintAccountMatchCheckFailed.into(),
+            Rule::MintAccountAmount { .. } => RuleSetError::MintAccountAmountCheckFailed.into(),
+            Rule::PubkeySortedListMatch { .. } => {
+                RuleSetError::PubkeySortedListMatchCheckFailed.into()
+            }
+            Rule::ProgramOwnedSortedList { .. } => {
+                RuleSetError::ProgramOwnedSortedListCheckFailed.into()
+            }
+            Rule::PubkeyTreeExclusion { .. } => RuleSetError::PubkeyTreeExclusionCheckFailed.into(),
+            Rule::ProgramOwnedTreeExclusion { .. } => {
+                RuleSetError::ProgramOwnedTreeExclusionCheckFailed.into()
+            }
+            Rule::Quota { .. } => RuleSetError::QuotaCheckFailed.into(),
+            Rule::UsageLimit { .. } => RuleSetError::UsageLimitCheckFailed.into(),
+            Rule::Nonce { .. } => RuleSetError::NonceCheckFailed.into(),
+            Rule::AccountDataMatch { .. } => RuleSetError::AccountDataMatchCheckFailed.into(),
+            Rule::Lamports { .. } => RuleSetError::LamportsCheckFailed.into(),
+            Rule::RentExempt { .. } => RuleSetError::RentExemptCheckFailed.into(),
+            Rule::IsProgram { .. } => RuleSetError::IsProgramCheckFailed.into(),
+            Rule::ProgramUpgradeAuthority { .. } => {
+                RuleSetError::ProgramUpgradeAuthorityCheckFailed.into()
+            }
+            Rule::AssociatedTokenAccountMatch { .. } => {
+                RuleSetError::AssociatedTokenAccountMatchCheckFailed.into()
+            }
+            Rule::Deny { code, .. } | Rule::WithError { code, .. } => owner_error(*code),
         }
     }
 }
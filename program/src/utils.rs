@@ -1,26 +1,34 @@
 //! Utilities for the program
 use crate::{
     error::RuleSetError,
-    payload::ProofInfo,
+    payload::{ExclusionProofInfo, Payload, PayloadType, ProofInfo},
+    pda::PREFIX,
     state::{
-        Rule, RuleSetHeader, RuleSetRevisionMapV1, RuleSetV1, RULE_SET_REV_MAP_VERSION,
-        RULE_SET_SERIALIZED_HEADER_LEN,
+        Rule, RuleSetHeader, RuleSetRevisionMapV1, RuleSetV2, RULE_SET_LIB_VERSION,
+        RULE_SET_REV_MAP_VERSION, RULE_SET_SERIALIZED_HEADER_LEN, RULE_SET_V2_LIB_VERSION,
     },
 };
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(not(feature = "curve25519-syscall"))]
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use safe_token::state::{Account, Mint};
+#[cfg(feature = "curve25519-syscall")]
+use safe_zk_token_sdk::curve25519::edwards::{validate_edwards, PodEdwardsPoint};
 use solana_program::{
     account_info::AccountInfo,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    ed25519_program,
     entrypoint::ProgramResult,
+    instruction::Instruction,
     msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
+    program_pack::Pack,
     pubkey::Pubkey,
     rent::Rent,
     system_instruction,
     sysvar::Sysvar,
 };
-// TODO: Uncomment this when the syscall is available.
-//use safe_zk_token_sdk::curve25519::curve_syscall_traits::CURVE25519_EDWARDS;
 
 /// Create account almost from scratch, lifted from
 /// <https://github.com/fair-exchange/safecoin-program-library/tree/master/associated-token-account/program/src/processor.rs#L51-L98>
@@ -118,6 +126,96 @@ pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
     }
 }
 
+/// Unpack a token account, checking that it is owned by the token program.
+pub fn unpack_token_account(account_info: &AccountInfo) -> Result<Account, ProgramError> {
+    assert_owned_by(account_info, &safe_token::ID)?;
+
+    let data = account_info
+        .data
+        .try_borrow()
+        .map_err(|_| ProgramError::AccountBorrowFailed)?;
+
+    Account::unpack(&data)
+}
+
+/// Unpack a mint account, checking that it is owned by the token program.
+pub fn unpack_mint(account_info: &AccountInfo) -> Result<Mint, ProgramError> {
+    assert_owned_by(account_info, &safe_token::ID)?;
+
+    let data = account_info
+        .data
+        .try_borrow()
+        .map_err(|_| ProgramError::AccountBorrowFailed)?;
+
+    Mint::unpack(&data)
+}
+
+/// The bincode tag of the `UpgradeableLoaderState::Program` variant.
+const UPGRADEABLE_PROGRAM_TAG: [u8; 4] = [2, 0, 0, 0];
+
+/// The bincode tag of the `UpgradeableLoaderState::ProgramData` variant.
+const UPGRADEABLE_PROGRAM_DATA_TAG: [u8; 4] = [3, 0, 0, 0];
+
+/// Get the address of the program data account of a program deployed with the upgradeable BPF
+/// loader.  The account data is read directly to avoid a bincode dependency.
+pub fn get_program_data_address(program_info: &AccountInfo) -> Result<Pubkey, ProgramError> {
+    assert_owned_by(program_info, &bpf_loader_upgradeable::ID)?;
+
+    let data = program_info
+        .data
+        .try_borrow()
+        .map_err(|_| ProgramError::AccountBorrowFailed)?;
+
+    match data.get(..UpgradeableLoaderState::size_of_program()) {
+        Some(state) if state[..4] == UPGRADEABLE_PROGRAM_TAG => {
+            Pubkey::try_from(&state[4..]).map_err(|_| ProgramError::InvalidAccountData)
+        }
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Check whether an account is a program data account of the upgradeable BPF loader.  The account
+/// is closed along with the program it belongs to.
+pub fn is_program_data(program_data_info: &AccountInfo) -> Result<bool, ProgramError> {
+    if *program_data_info.owner != bpf_loader_upgradeable::ID {
+        return Ok(false);
+    }
+
+    let data = program_data_info
+        .data
+        .try_borrow()
+        .map_err(|_| ProgramError::AccountBorrowFailed)?;
+
+    Ok(data.get(..4) == Some(&UPGRADEABLE_PROGRAM_DATA_TAG[..]))
+}
+
+/// Get the upgrade authority stored in a program data account of the upgradeable BPF loader.
+/// Returns `None` if the program is immutable.
+pub fn get_upgrade_authority(
+    program_data_info: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    assert_owned_by(program_data_info, &bpf_loader_upgradeable::ID)?;
+
+    let data = program_data_info
+        .data
+        .try_borrow()
+        .map_err(|_| ProgramError::AccountBorrowFailed)?;
+
+    // The metadata is the tag, the deployment slot and the optional upgrade authority.
+    let metadata = match data.get(..UpgradeableLoaderState::size_of_programdata_metadata()) {
+        Some(metadata) if metadata[..4] == UPGRADEABLE_PROGRAM_DATA_TAG => metadata,
+        _ => return Err(ProgramError::InvalidAccountData),
+    };
+
+    match metadata[12] {
+        0 => Ok(None),
+        1 => Pubkey::try_from(&metadata[13..])
+            .map(Some)
+            .map_err(|_| ProgramError::InvalidAccountData),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
 /// Compute the root of a Merkle tree given a leaf and a proof.  Uses a constant value
 /// of 0x01 as an input to the hashing function along with the values to be hashed.
 pub fn compute_merkle_root(leaf: &Pubkey, merkle_proof: &ProofInfo) -> [u8; 32] {
@@ -137,6 +235,59 @@ pub fn compute_merkle_root(leaf: &Pubkey, merkle_proof: &ProofInfo) -> [u8; 32]
     computed_hash
 }
 
+/// Compute the root of a sorted Merkle tree given a leaf, its index, and a proof.  Leaves are
+/// hashed with a constant value of 0x00 so that they cannot be confused with the inner nodes,
+/// which are hashed with 0x01 in the order given by the index.  Returns `None` if the index does
+/// not fit in a tree of the proof's depth.
+pub fn compute_indexed_merkle_root(
+    leaf: &Pubkey,
+    index: u32,
+    proof: &[[u8; 32]],
+) -> Option<[u8; 32]> {
+    let mut computed_hash = solana_program::keccak::hashv(&[&[0x00], leaf.as_ref()]).0;
+    let mut position = index;
+    for proof_element in proof.iter() {
+        if position & 1 == 0 {
+            // Hash(current computed hash + current element of the proof).
+            computed_hash =
+                solana_program::keccak::hashv(&[&[0x01], &computed_hash, proof_element]).0;
+        } else {
+            // Hash(current element of the proof + current computed hash).
+            computed_hash =
+                solana_program::keccak::hashv(&[&[0x01], proof_element, &computed_hash]).0;
+        }
+        position >>= 1;
+    }
+
+    if position != 0 {
+        return None;
+    }
+
+    Some(computed_hash)
+}
+
+/// Check that a `Pubkey` is not a leaf of a sorted Merkle tree, by checking that it falls strictly
+/// between two adjacent leaves of the tree.
+pub fn is_excluded_from_tree(
+    key: &Pubkey,
+    root: &[u8; 32],
+    proof_info: &ExclusionProofInfo,
+) -> bool {
+    if !(proof_info.left < *key && *key < proof_info.right) {
+        return false;
+    }
+
+    let right_index = match proof_info.index.checked_add(1) {
+        Some(right_index) => right_index,
+        None => return false,
+    };
+
+    compute_indexed_merkle_root(&proof_info.left, proof_info.index, &proof_info.left_proof)
+        == Some(*root)
+        && compute_indexed_merkle_root(&proof_info.right, right_index, &proof_info.right_proof)
+            == Some(*root)
+}
+
 /// Get a revision map by looking at the header, finding its location, and deserializing it.
 pub fn get_existing_revision_map(
     rule_set_pda_info: &AccountInfo,
@@ -176,6 +327,96 @@ pub fn get_existing_revision_map(
     }
 }
 
+/// Get a `RuleSet` revision by looking up its location in the revision map and deserializing it.
+/// If no revision is specified, the latest revision is used.  `RuleSetV1` revisions deserialize
+/// as a `RuleSetV2` without any definitions.
+///
+/// The `RuleSet` account must be owned by this program and derived from the owner and name
+/// stored in the deserialized `RuleSet`.
+pub fn get_existing_rule_set(
+    rule_set_pda_info: &AccountInfo,
+    rule_set_revision: Option<usize>,
+) -> Result<RuleSetV2, ProgramError> {
+    // `RuleSet` must be owned by this program.
+    if *rule_set_pda_info.owner != crate::ID {
+        return Err(RuleSetError::IncorrectOwner.into());
+    }
+
+    // `RuleSet` must not be empty.
+    if rule_set_pda_info.data_is_empty() {
+        return Err(RuleSetError::DataIsEmpty.into());
+    }
+
+    // Get existing revision map and its serialized length.
+    let (revision_map, rev_map_location) = get_existing_revision_map(rule_set_pda_info)?;
+
+    // Use the user-provided revision number to look up the `RuleSet` revision location in the PDA.
+    let (start, end) = match rule_set_revision {
+        Some(revision) => {
+            let start = revision_map
+                .rule_set_revisions
+                .get(revision)
+                .ok_or(RuleSetError::RuleSetRevisionNotAvailable)?;
+
+            let end_index = revision
+                .checked_add(1)
+                .ok_or(RuleSetError::NumericalOverflow)?;
+
+            let end = revision_map
+                .rule_set_revisions
+                .get(end_index)
+                .unwrap_or(&rev_map_location);
+            (*start, *end)
+        }
+        None => {
+            let start = revision_map
+                .rule_set_revisions
+                .last()
+                .ok_or(RuleSetError::RuleSetRevisionNotAvailable)?;
+            (*start, rev_map_location)
+        }
+    };
+
+    // Borrow the existing `RuleSet` PDA data.
+    let data = rule_set_pda_info
+        .data
+        .try_borrow()
+        .map_err(|_| ProgramError::AccountBorrowFailed)?;
+
+    // Increment starting location by size of lib version.
+    let lib_version_start = start;
+    let start = start
+        .checked_add(1)
+        .ok_or(RuleSetError::NumericalOverflow)?;
+
+    if end >= rule_set_pda_info.data_len() {
+        return Err(RuleSetError::DataTypeMismatch.into());
+    }
+
+    // Check `RuleSet` lib version and deserialize `RuleSet`.
+    let rule_set = match data.get(lib_version_start) {
+        Some(&RULE_SET_LIB_VERSION) | Some(&RULE_SET_V2_LIB_VERSION) => {
+            rmp_serde::from_slice::<RuleSetV2>(&data[start..end])
+                .map_err(|_| RuleSetError::MessagePackDeserializationError)?
+        }
+        Some(_) => return Err(RuleSetError::UnsupportedRuleSetVersion.into()),
+        None => return Err(RuleSetError::DataTypeMismatch.into()),
+    };
+
+    // Check `RuleSet` account info derivation.
+    let _bump = assert_derivation(
+        &crate::ID,
+        rule_set_pda_info.key,
+        &[
+            PREFIX.as_bytes(),
+            rule_set.owner().as_ref(),
+            rule_set.name().as_bytes(),
+        ],
+    )?;
+
+    Ok(rule_set)
+}
+
 /// Get the latest revision number stored on the revision map.
 ///
 /// This will first deserialize the header to find the map location and then deserialize the
@@ -191,22 +432,21 @@ pub fn get_latest_revision(rule_set_pda_info: &AccountInfo) -> Result<Option<usi
     }
 }
 
-/// Return whether the pubkey is on the Edwards 25519 curve.
+/// Return whether the pubkey is on the Edwards 25519 curve.  By default this decompresses the
+/// point in pure Rust.  When the `curve25519-syscall` feature is enabled, the curve point
+/// validation syscall is used instead.
 pub fn is_on_curve(pubkey: &Pubkey) -> bool {
-    let _point = pubkey.to_bytes();
-    let mut _validate_result = 0u8;
-    // TODO: Uncomment this when the syscall is available.
-    // let result = unsafe {
-    //     solana_program::syscalls::sol_curve_validate_point(
-    //         CURVE25519_EDWARDS,
-    //         &point as *const u8,
-    //         &mut validate_result,
-    //     )
-    // };
-
-    // For now return false instead of checking the result.
-    // result == 0
-    false
+    #[cfg(feature = "curve25519-syscall")]
+    {
+        validate_edwards(&PodEdwardsPoint(pubkey.to_bytes()))
+    }
+
+    #[cfg(not(feature = "curve25519-syscall"))]
+    {
+        CompressedEdwardsY::from_slice(pubkey.as_ref())
+            .decompress()
+            .is_some()
+    }
 }
 
 /// See if a slice contains all zeroes.  Useful for checking an account's data.
@@ -224,7 +464,7 @@ pub fn is_zeroed(buf: &[u8]) -> bool {
 }
 
 /// This function returns the rule for an operation by recursively searching through fallbacks
-pub fn get_operation(operation: String, rule_set: &RuleSetV1) -> Result<&Rule, ProgramError> {
+pub fn get_operation(operation: String, rule_set: &RuleSetV2) -> Result<&Rule, ProgramError> {
     let rule = rule_set.get(operation.to_string());
 
     match rule {
@@ -243,3 +483,71 @@ pub fn get_operation(operation: String, rule_set: &RuleSetV1) -> Result<&Rule, P
         None => Err(RuleSetError::OperationNotFound.into()),
     }
 }
+
+/// Size of the Ed25519 program instruction data header holding the signature count and padding.
+const ED25519_HEADER_LEN: usize = 2;
+
+/// Size of each signature's offsets in the Ed25519 program instruction data.
+const ED25519_OFFSETS_LEN: usize = 14;
+
+/// Instruction index used by the Ed25519 program to refer to its own instruction data.
+const ED25519_CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Get the message an authority signs to permit an operation.  The message is the Borsh
+/// serialization of the mint, the operation, and the `Payload` values of the fields in order.
+pub fn get_permit_message(
+    mint: &Pubkey,
+    operation: &str,
+    payload: &Payload,
+    fields: &[String],
+) -> Result<Vec<u8>, ProgramError> {
+    let values = fields
+        .iter()
+        .map(|field| payload.get(field).cloned())
+        .collect::<Option<Vec<PayloadType>>>()
+        .ok_or(RuleSetError::MissingPayloadValue)?;
+
+    (*mint, operation.to_string(), values)
+        .try_to_vec()
+        .map_err(|_| RuleSetError::BorshSerializationError.into())
+}
+
+/// Return whether an Ed25519 program instruction verifies a signature by the signer over the
+/// message.  Only signatures whose public key, signature, and message are all stored in the
+/// Ed25519 program instruction itself are considered.
+pub fn is_ed25519_signed(instruction: &Instruction, signer: &Pubkey, message: &[u8]) -> bool {
+    if instruction.program_id != ed25519_program::ID {
+        return false;
+    }
+
+    let data = &instruction.data;
+    let num_signatures = match data.first() {
+        Some(num_signatures) => *num_signatures as usize,
+        None => return false,
+    };
+
+    (0..num_signatures).any(|i| {
+        let start = ED25519_HEADER_LEN + i * ED25519_OFFSETS_LEN;
+        let offsets = match data.get(start..start + ED25519_OFFSETS_LEN) {
+            Some(offsets) => offsets,
+            None => return false,
+        };
+
+        let read = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+
+        // The signature, public key, and message must all be in this instruction.
+        if read(2) != ED25519_CURRENT_INSTRUCTION
+            || read(6) != ED25519_CURRENT_INSTRUCTION
+            || read(12) != ED25519_CURRENT_INSTRUCTION
+        {
+            return false;
+        }
+
+        let public_key_offset = read(4) as usize;
+        let message_offset = read(8) as usize;
+        let message_size = read(10) as usize;
+
+        data.get(public_key_offset..public_key_offset + 32) == Some(signer.as_ref())
+            && data.get(message_offset..message_offset + message_size) == Some(message)
+    })
+}
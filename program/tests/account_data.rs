@@ -0,0 +1,209 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{AccountDataCheck, CompareOp, Rule, RuleSetV1},
+};
+use solana_program::program_pack::Pack;
+use solana_program_test::tokio;
+use solana_sdk::{instruction::AccountMeta, signature::Signer, signer::keypair::Keypair};
+use utils::{create_mint, create_validate_ix, program_test, Operation, PayloadKey};
+
+#[tokio::test]
+async fn test_account_data_match() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing only mint accounts whose mint authority is the collection authority.
+    // The mint authority is stored after the 4-byte `COption` tag.
+    let collection_authority = Keypair::new().pubkey();
+    let rule = Rule::All {
+        rules: vec![
+            Rule::AccountDataMatch {
+                field: PayloadKey::Destination.to_string(),
+                check: AccountDataCheck::Length {
+                    operator: CompareOp::Eq,
+                    length: safe_token::state::Mint::LEN as u64,
+                },
+            },
+            Rule::AccountDataMatch {
+                field: PayloadKey::Destination.to_string(),
+                check: AccountDataCheck::Bytes {
+                    offset: 4,
+                    bytes: collection_authority.to_bytes().to_vec(),
+                },
+            },
+        ],
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a mint of the collection and a mint of another authority.
+    let mint = Keypair::new();
+    create_mint(&mut context, &mint, &collection_authority, None, 0)
+        .await
+        .unwrap();
+
+    let other_mint = Keypair::new();
+    let payer_pubkey = context.payer.pubkey();
+    create_mint(&mut context, &other_mint, &payer_pubkey, None, 0)
+        .await
+        .unwrap();
+
+    // --------------------------------
+    // Validate fail missing account
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(mint.pubkey()),
+        )]),
+        vec![],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MissingAccount);
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Pass the mint of the other authority as the destination.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(other_mint.pubkey()),
+        )]),
+        vec![AccountMeta::new_readonly(other_mint.pubkey(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::AccountDataMatchCheckFailed);
+
+    // Pass the RuleSet as the destination, which does not have the length of a mint.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(rule_set_addr),
+        )]),
+        vec![AccountMeta::new_readonly(rule_set_addr, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::AccountDataMatchCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Pass the mint of the collection as the destination.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(mint.pubkey()),
+        )]),
+        vec![AccountMeta::new_readonly(mint.pubkey(), false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_account_data_discriminator() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring an account to start with a discriminator.
+    let rule = Rule::AccountDataMatch {
+        field: PayloadKey::Destination.to_string(),
+        check: AccountDataCheck::Discriminator([1, 2, 3, 4, 5, 6, 7, 8]),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a mint, whose data does not start with the discriminator.
+    let mint = Keypair::new();
+    let payer_pubkey = context.payer.pubkey();
+    create_mint(&mut context, &mint, &payer_pubkey, None, 0)
+        .await
+        .unwrap();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(mint.pubkey()),
+        )]),
+        vec![AccountMeta::new_readonly(mint.pubkey(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::AccountDataMatchCheckFailed);
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{CompareOp, Rule, RuleSetV1},
+};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use utils::{create_validate_ix, program_test, Operation, PayloadKey};
+
+// The field holding the amount to compare against, e.g. a delegate allowance.
+const ALLOWANCE_FIELD: &str = "Allowance";
+
+#[tokio::test]
+async fn test_less_than_amount_field() {
+    parametric_amount_field_check(CompareOp::Lt, 100, 100, 99).await;
+}
+
+#[tokio::test]
+async fn test_less_than_or_equal_to_amount_field() {
+    parametric_amount_field_check(CompareOp::LtEq, 100, 101, 100).await;
+}
+
+#[tokio::test]
+async fn equal_to_amount_field_fail_less_than() {
+    parametric_amount_field_check(CompareOp::Eq, 100, 99, 100).await;
+}
+
+#[tokio::test]
+async fn equal_to_amount_field_fail_greater_than() {
+    parametric_amount_field_check(CompareOp::Eq, 100, 101, 100).await;
+}
+
+#[tokio::test]
+async fn test_greater_than_or_equal_to_amount_field() {
+    parametric_amount_field_check(CompareOp::GtEq, 100, 99, 100).await;
+}
+
+#[tokio::test]
+async fn test_greater_than_amount_field() {
+    parametric_amount_field_check(CompareOp::Gt, 100, 100, 101).await;
+}
+
+async fn parametric_amount_field_check(
+    operator: CompareOp,
+    allowance: u64,
+    fail_amount: u64,
+    pass_amount: u64,
+) {
+    let mut context = program_test().start_with_context().await;
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a rule.
+    let amount_field_check = Rule::AmountField {
+        field: PayloadKey::Amount.to_string(),
+        operator,
+        other_field: ALLOWANCE_FIELD.to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            amount_field_check,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // Store a payload of data with an amount not allowed by the AmountField Rule.
+    let payload = Payload::from([
+        (
+            PayloadKey::Amount.to_string(),
+            PayloadType::Number(fail_amount),
+        ),
+        (ALLOWANCE_FIELD.to_string(), PayloadType::Number(allowance)),
+    ]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::AmountFieldCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store a payload of data with an amount allowed by the AmountField Rule.
+    let payload = Payload::from([
+        (
+            PayloadKey::Amount.to_string(),
+            PayloadType::Number(pass_amount),
+        ),
+        (ALLOWANCE_FIELD.to_string(), PayloadType::Number(allowance)),
+    ]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
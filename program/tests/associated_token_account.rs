@@ -0,0 +1,116 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{Rule, RuleSetV1},
+};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use utils::{
+    create_associated_token_account, create_mint, create_validate_ix, program_test, Operation,
+    PayloadKey,
+};
+
+#[tokio::test]
+async fn test_associated_token_account_match() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring the destination to be the associated token account of the
+    // destination owner.
+    let rule = Rule::AssociatedTokenAccountMatch {
+        field: PayloadKey::Destination.to_string(),
+        wallet_field: PayloadKey::Holder.to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a mint and associated token accounts for two wallets.
+    let mint = Keypair::new();
+    let payer_pubkey = context.payer.pubkey();
+    create_mint(&mut context, &mint, &payer_pubkey, None, 0)
+        .await
+        .unwrap();
+
+    let holder = Keypair::new();
+    let token_account = create_associated_token_account(&mut context, &holder, &mint.pubkey())
+        .await
+        .unwrap();
+
+    let other_holder = Keypair::new();
+    let other_token_account =
+        create_associated_token_account(&mut context, &other_holder, &mint.pubkey())
+            .await
+            .unwrap();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Substitute the token account of another wallet.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        Payload::from([
+            (
+                PayloadKey::Destination.to_string(),
+                PayloadType::Pubkey(other_token_account),
+            ),
+            (
+                PayloadKey::Holder.to_string(),
+                PayloadType::Pubkey(holder.pubkey()),
+            ),
+        ]),
+        vec![],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::AssociatedTokenAccountMatchCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        Payload::from([
+            (
+                PayloadKey::Destination.to_string(),
+                PayloadType::Pubkey(token_account),
+            ),
+            (
+                PayloadKey::Holder.to_string(),
+                PayloadType::Pubkey(holder.pubkey()),
+            ),
+        ]),
+        vec![],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
@@ -351,7 +351,7 @@ async fn create_rule_set() {
 }
 
 #[tokio::test]
-async fn wallet_to_wallet_unimplemented() {
+async fn wallet_to_wallet() {
     let mut context = program_test().start_with_context().await;
     let rule_set_addr = create_royalty_rule_set(&mut context).await;
 
@@ -396,20 +396,8 @@ async fn wallet_to_wallet_unimplemented() {
         .unwrap()
         .instruction();
 
-    // Validate fail operation.
-    let err =
-        process_failing_validate_ix!(&mut context, validate_ix, vec![], Some(ADDITIONAL_COMPUTE))
-            .await;
-
-    // Check that error is what we expect.  The `IsWallet` rule currently returns `NotImplemented`.
-    match err {
-        solana_program_test::BanksClientError::TransactionError(
-            TransactionError::InstructionError(_, InstructionError::Custom(error)),
-        ) => {
-            assert_eq!(error, RuleSetError::NotImplemented as u32);
-        }
-        _ => panic!("Unexpected error: {:?}", err),
-    }
+    // Validate operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], Some(ADDITIONAL_COMPUTE)).await;
 }
 
 #[tokio::test]
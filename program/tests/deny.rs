@@ -0,0 +1,138 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{CompareOp, Rule, RuleSetV1},
+};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use utils::{create_validate_ix, program_test, Operation, PayloadKey};
+
+#[tokio::test]
+async fn test_deny() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule.
+    let rule = Rule::Deny {
+        code: 7,
+        message: "Transfers are paused".to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, Payload::default(), vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_owner_error!(err, 7);
+}
+
+#[tokio::test]
+async fn test_with_error() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule.
+    let rule = Rule::WithError {
+        rule: Box::new(Rule::Amount {
+            amount: 5,
+            operator: CompareOp::Lt,
+            field: PayloadKey::Amount.to_string(),
+        }),
+        code: 42,
+        message: "Amount too large".to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate error
+    // --------------------------------
+    // Create a `validate` instruction without the amount.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, Payload::default(), vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.  Errors are not replaced.
+    assert_custom_error!(err, RuleSetError::MissingPayloadValue);
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store a payload of data with the WRONG amount.
+    let payload = Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(5))]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_owner_error!(err, 42);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store a payload of data with the correct amount.
+    let payload = Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(4))]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
@@ -0,0 +1,239 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{Rule, RuleSetV1},
+    utils::get_permit_message,
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey, sysvar};
+use solana_program_test::{tokio, BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    ed25519_instruction::new_ed25519_instruction, instruction::AccountMeta, signature::Signer,
+    signer::keypair::Keypair, transaction::Transaction,
+};
+use utils::{create_validate_ix, program_test, Operation, PayloadKey};
+
+const EXPIRY_FIELD: &str = "Expiry";
+
+#[tokio::test]
+async fn test_ed25519_permit() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring a permit from the creator binding the amount and destination.
+    let creator = Keypair::new();
+
+    let rule = Rule::Ed25519Permit {
+        authority: creator.pubkey(),
+        fields: vec![
+            PayloadKey::Amount.to_string(),
+            PayloadKey::Destination.to_string(),
+        ],
+        expiry_field: Some(EXPIRY_FIELD.to_string()),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let destination = Keypair::new().pubkey();
+    let operation = Operation::Transfer {
+        scenario: utils::TransferScenario::Holder,
+    }
+    .to_string();
+
+    // Store the payload of data permitted by the creator.
+    let payload = create_payload(1, destination, u64::MAX);
+    let permit_ix = create_permit_ix(
+        &creator,
+        &get_permit_message(
+            &mint,
+            &operation,
+            &payload,
+            &[
+                PayloadKey::Amount.to_string(),
+                PayloadKey::Destination.to_string(),
+                EXPIRY_FIELD.to_string(),
+            ],
+        )
+        .unwrap(),
+    );
+
+    // --------------------------------
+    // Validate fail missing permit
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload.clone(),
+        vec![AccountMeta::new_readonly(sysvar::instructions::id(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::Ed25519PermitCheckFailed);
+
+    // --------------------------------
+    // Validate fail different amount
+    // --------------------------------
+    // Store a payload of data with an amount that was NOT permitted.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        create_payload(2, destination, u64::MAX),
+        vec![AccountMeta::new_readonly(sysvar::instructions::id(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_permit_tx(&mut context, validate_ix, permit_ix.clone())
+        .await
+        .expect_err("Validation should fail");
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::Ed25519PermitCheckFailed);
+
+    // --------------------------------
+    // Validate fail wrong authority
+    // --------------------------------
+    // Create a permit signed by someone other than the creator.
+    let other_permit_ix = create_permit_ix(
+        &Keypair::new(),
+        &get_permit_message(
+            &mint,
+            &operation,
+            &payload,
+            &[
+                PayloadKey::Amount.to_string(),
+                PayloadKey::Destination.to_string(),
+                EXPIRY_FIELD.to_string(),
+            ],
+        )
+        .unwrap(),
+    );
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload.clone(),
+        vec![AccountMeta::new_readonly(sysvar::instructions::id(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_permit_tx(&mut context, validate_ix, other_permit_ix)
+        .await
+        .expect_err("Validation should fail");
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::Ed25519PermitCheckFailed);
+
+    // --------------------------------
+    // Validate fail expired
+    // --------------------------------
+    // Create a permit that has already expired.
+    let expired_payload = create_payload(1, destination, 0);
+    let expired_permit_ix = create_permit_ix(
+        &creator,
+        &get_permit_message(
+            &mint,
+            &operation,
+            &expired_payload,
+            &[
+                PayloadKey::Amount.to_string(),
+                PayloadKey::Destination.to_string(),
+                EXPIRY_FIELD.to_string(),
+            ],
+        )
+        .unwrap(),
+    );
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        expired_payload,
+        vec![AccountMeta::new_readonly(sysvar::instructions::id(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_permit_tx(&mut context, validate_ix, expired_permit_ix)
+        .await
+        .expect_err("Validation should fail");
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::Ed25519PermitCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload,
+        vec![AccountMeta::new_readonly(sysvar::instructions::id(), false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_permit_tx(&mut context, validate_ix, permit_ix)
+        .await
+        .expect("Validation should pass");
+}
+
+fn create_payload(amount: u64, destination: Pubkey, expiry: u64) -> Payload {
+    Payload::from([
+        (PayloadKey::Amount.to_string(), PayloadType::Number(amount)),
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(destination),
+        ),
+        (EXPIRY_FIELD.to_string(), PayloadType::Number(expiry)),
+    ])
+}
+
+fn create_permit_ix(signer: &Keypair, message: &[u8]) -> Instruction {
+    let keypair = ed25519_dalek::Keypair::from_bytes(&signer.to_bytes()).unwrap();
+    new_ed25519_instruction(&keypair, message)
+}
+
+// The `validate` instruction is placed first so that its errors are reported at index 0.
+async fn process_permit_tx(
+    context: &mut ProgramTestContext,
+    validate_ix: Instruction,
+    permit_ix: Instruction,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[validate_ix, permit_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
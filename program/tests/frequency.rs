@@ -2,18 +2,21 @@
 
 pub mod utils;
 
+use borsh::BorshDeserialize;
 use lpl_token_auth_rules::{
     error::RuleSetError,
     instruction::{builders::ValidateBuilder, InstructionBuilder, ValidateArgs},
-    payload::Payload,
+    payload::{Payload, PayloadType},
     pda::find_rule_set_state_address,
-    state::{Rule, RuleSetV1},
+    state::{Key, Rule, RuleSetStateAccount, RuleSetV1, RuleState},
 };
 use solana_program::program_error::ProgramError;
 use solana_program_test::tokio;
 use solana_program_test::BanksClientError;
 use solana_sdk::{signature::Signer, signer::keypair::Keypair, transaction::TransactionError};
-use utils::{program_test, Operation};
+use utils::{
+    advance_clock, create_validate_ix, program_test, Operation, PayloadKey, RuleStateAccounts,
+};
 
 #[tokio::test]
 async fn test_frequency() {
@@ -24,21 +27,19 @@ async fn test_frequency() {
     // --------------------------------
     // Create a Rule.
     let rule_authority = Keypair::new();
-    let rule = Rule::Frequency {
+    let frequency = Rule::Frequency {
         authority: rule_authority.pubkey(),
+        period: 60 * 60,
     };
 
     // Create a RuleSet.
+    let operation = Operation::Transfer {
+        scenario: utils::TransferScenario::Holder,
+    }
+    .to_string();
+
     let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
-    rule_set
-        .add(
-            Operation::Transfer {
-                scenario: utils::TransferScenario::Holder,
-            }
-            .to_string(),
-            rule,
-        )
-        .unwrap();
+    rule_set.add(operation.clone(), frequency.clone()).unwrap();
 
     println!("{:#?}", rule_set);
 
@@ -91,25 +92,18 @@ async fn test_frequency() {
     let (rule_set_state_addr, _rule_set_bump) =
         find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
 
-    // Create a `validate` instruction.
-    let validate_ix = ValidateBuilder::new()
-        .rule_set_pda(rule_set_addr)
-        .mint(mint)
-        .payer(context.payer.pubkey())
-        .rule_authority(context.payer.pubkey())
-        .rule_set_state_pda(rule_set_state_addr)
-        .additional_rule_accounts(vec![])
-        .build(ValidateArgs::V1 {
-            operation: Operation::Transfer {
-                scenario: utils::TransferScenario::Holder,
-            }
-            .to_string(),
-            payload: Payload::default(),
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::default(),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: context.payer.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
             update_rule_state: true,
-            rule_set_revision: None,
-        })
-        .unwrap()
-        .instruction();
+        }),
+    );
 
     // Fail to validate Transfer operation.
     let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
@@ -118,33 +112,203 @@ async fn test_frequency() {
     assert_custom_error!(err, RuleSetError::RuleAuthorityIsNotSigner);
 
     // --------------------------------
-    // Validate not implemented
-    // (this will become pass later)
+    // Validate pass
     // --------------------------------
-    // Create a `validate` instruction.
-    let validate_ix = ValidateBuilder::new()
-        .rule_set_pda(rule_set_addr)
-        .mint(mint)
-        .payer(context.payer.pubkey())
-        .rule_authority(rule_authority.pubkey())
-        .rule_set_state_pda(rule_set_state_addr)
-        .additional_rule_accounts(vec![])
-        .build(ValidateArgs::V1 {
-            operation: Operation::Transfer {
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::default(),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that the state PDA was created and the last update stored.
+    let rule_set_state_account = context
+        .banks_client
+        .get_account(rule_set_state_addr)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(rule_set_state_account.owner, lpl_token_auth_rules::ID);
+
+    let state_account = RuleSetStateAccount::try_from_slice(&rule_set_state_account.data).unwrap();
+    assert_eq!(state_account.key, Key::RuleSetState);
+    assert!(matches!(
+        state_account
+            .states
+            .get(&RuleSetStateAccount::state_key(&frequency, &operation).unwrap()),
+        Some(RuleState::Frequency { .. })
+    ));
+
+    // --------------------------------
+    // Validate fail within period
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::default(),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: false,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::FrequencyCheckFailed);
+
+    // --------------------------------
+    // Validate pass after period
+    // --------------------------------
+    // Move the clock past the period.
+    advance_clock(&mut context, 60 * 60).await;
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::default(),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+}
+
+#[tokio::test]
+async fn test_frequency_with_quota() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule combining a frequency with a quota, so both store their state in the same
+    // state PDA.
+    let rule_authority = Keypair::new();
+    let frequency = Rule::Frequency {
+        authority: rule_authority.pubkey(),
+        period: 60 * 60,
+    };
+    let quota = Rule::Quota {
+        name: "daily".to_string(),
+        authority: rule_authority.pubkey(),
+        field: PayloadKey::Amount.to_string(),
+        cap: 10,
+        period: 24 * 60 * 60,
+    };
+
+    let rule = Rule::All {
+        rules: vec![frequency.clone(), quota.clone()],
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
                 scenario: utils::TransferScenario::Holder,
             }
             .to_string(),
-            payload: Payload::default(),
+            rule,
+        )
+        .unwrap();
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(4))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
             update_rule_state: true,
-            rule_set_revision: None,
-        })
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that both rules stored their state.
+    let rule_set_state_account = context
+        .banks_client
+        .get_account(rule_set_state_addr)
+        .await
         .unwrap()
-        .instruction();
+        .unwrap();
+
+    let state_account = RuleSetStateAccount::try_from_slice(&rule_set_state_account.data).unwrap();
+    let operation = Operation::Transfer {
+        scenario: utils::TransferScenario::Holder,
+    }
+    .to_string();
+
+    assert!(matches!(
+        state_account
+            .states
+            .get(&RuleSetStateAccount::state_key(&frequency, &operation).unwrap()),
+        Some(RuleState::Frequency { .. })
+    ));
+    assert!(matches!(
+        state_account
+            .states
+            .get(&RuleSetStateAccount::state_key(&quota, &operation).unwrap()),
+        Some(RuleState::Quota { amount: 4, .. })
+    ));
+
+    // --------------------------------
+    // Validate fail within period
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(1))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
 
     // Fail to validate Transfer operation.
     let err =
         process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
 
     // Check that error is what we expect.
-    assert_custom_error!(err, RuleSetError::NotImplemented);
+    assert_custom_error!(err, RuleSetError::FrequencyCheckFailed);
 }
@@ -0,0 +1,116 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{CompareOp, Rule, RuleSetV1},
+};
+use solana_program_test::tokio;
+use solana_sdk::{instruction::AccountMeta, signature::Signer, signer::keypair::Keypair};
+use utils::{create_validate_ix, program_test, Operation, PayloadKey};
+
+#[tokio::test]
+async fn test_if_then_else() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule where transfers of 10 or more require an additional signer.
+    let adtl_signer = Keypair::new();
+
+    let overall_rule = Rule::If {
+        condition: Box::new(Rule::Amount {
+            amount: 10,
+            operator: CompareOp::Lt,
+            field: PayloadKey::Amount.to_string(),
+        }),
+        then: Box::new(Rule::Pass),
+        r#else: Box::new(Rule::AdditionalSigner {
+            account: adtl_signer.pubkey(),
+        }),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            overall_rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate error
+    // --------------------------------
+    // Create a `validate` instruction without the amount needed by the condition.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, Payload::default(), vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.  The condition error is returned as is.
+    assert_custom_error!(err, RuleSetError::MissingPayloadValue);
+
+    // --------------------------------
+    // Validate pass then branch
+    // --------------------------------
+    // Store a payload of data with a small amount.
+    let payload = Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(5))]);
+
+    // Create a `validate` instruction without the additional signer.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Validate Transfer operation since the condition passed.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // --------------------------------
+    // Validate fail else branch
+    // --------------------------------
+    // Store a payload of data with a large amount.
+    let payload = Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(20))]);
+
+    // Create a `validate` instruction WITH the additional account but not as a signer.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload.clone(),
+        vec![AccountMeta::new_readonly(adtl_signer.pubkey(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.  The else branch error is reported.
+    assert_custom_error!(err, RuleSetError::AdditionalSignerCheckFailed);
+
+    // --------------------------------
+    // Validate pass else branch
+    // --------------------------------
+    // Create a `validate` instruction WITH the additional signer.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload,
+        vec![AccountMeta::new_readonly(adtl_signer.pubkey(), true)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&adtl_signer], None).await;
+}
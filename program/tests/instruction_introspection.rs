@@ -0,0 +1,188 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    instruction::{builders::ValidateBuilder, InstructionBuilder, ValidateArgs},
+    payload::Payload,
+    state::{PubkeyTarget, Rule, RuleSetV1},
+};
+use solana_program::sysvar;
+use solana_program_test::tokio;
+use solana_sdk::{
+    compute_budget, instruction::AccountMeta, signature::Signer, signer::keypair::Keypair,
+};
+use utils::{create_validate_ix, program_test, Operation};
+
+#[tokio::test]
+async fn test_transaction_includes_program() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring a Compute Budget instruction in the transaction.
+    let rule = Rule::TransactionIncludesProgram {
+        program: PubkeyTarget::Pubkey(compute_budget::id()),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail missing account
+    // --------------------------------
+    // Create a `validate` instruction WITHOUT the Instructions sysvar.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, Payload::default(), vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MissingAccount);
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Create a `validate` instruction WITH the Instructions sysvar.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::default(),
+        vec![AccountMeta::new_readonly(sysvar::instructions::id(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation since the transaction has no Compute Budget
+    // instruction.
+    let err = process_failing_validate_ix!(&mut context, validate_ix.clone(), vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::TransactionIncludesProgramCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Validate Transfer operation in a transaction that also sets the compute unit limit.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], Some(400_000)).await;
+}
+
+#[tokio::test]
+async fn test_top_level_program() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a RuleSet where one operation must be called directly, and another must be called
+    // through a marketplace program.
+    let marketplace = Keypair::new().pubkey();
+
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            Rule::TopLevelProgram {
+                program: PubkeyTarget::Pubkey(lpl_token_auth_rules::id()),
+            },
+        )
+        .unwrap();
+
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::SaleDelegate,
+            }
+            .to_string(),
+            Rule::TopLevelProgram {
+                program: PubkeyTarget::Pubkey(marketplace),
+            },
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Create a `validate` instruction for the operation requiring the marketplace.
+    let validate_ix = ValidateBuilder::new()
+        .rule_set_pda(rule_set_addr)
+        .mint(mint)
+        .additional_rule_accounts(vec![AccountMeta::new_readonly(
+            sysvar::instructions::id(),
+            false,
+        )])
+        .build(ValidateArgs::V1 {
+            operation: Operation::Transfer {
+                scenario: utils::TransferScenario::SaleDelegate,
+            }
+            .to_string(),
+            payload: Payload::default(),
+            update_rule_state: false,
+            rule_set_revision: None,
+        })
+        .unwrap()
+        .instruction();
+
+    // Fail to validate Transfer operation since the top-level instruction is `Validate` itself.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::TopLevelProgramCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Create a `validate` instruction for the operation that must be called directly.
+    let validate_ix = ValidateBuilder::new()
+        .rule_set_pda(rule_set_addr)
+        .mint(mint)
+        .additional_rule_accounts(vec![AccountMeta::new_readonly(
+            sysvar::instructions::id(),
+            false,
+        )])
+        .build(ValidateArgs::V1 {
+            operation: Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            payload: Payload::default(),
+            update_rule_state: false,
+            rule_set_revision: None,
+        })
+        .unwrap()
+        .instruction();
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
@@ -8,6 +8,7 @@ use lpl_token_auth_rules::{
     payload::{Payload, PayloadType},
     state::{Rule, RuleSetV1},
 };
+use solana_program::pubkey::Pubkey;
 use solana_program_test::tokio;
 use solana_sdk::{instruction::AccountMeta, signature::Signer, signer::keypair::Keypair};
 use utils::{program_test, Operation, PayloadKey};
@@ -40,16 +41,15 @@ async fn is_wallet() {
     let rule_set_addr =
         create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
 
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
     // --------------------------------
-    // Validate not implemented
-    // (this will become pass later)
+    // Validate pass
     // --------------------------------
     // Keypair to check.
     let wallet = Keypair::new();
 
-    // Create a Keypair to simulate a token mint address.
-    let mint = Keypair::new().pubkey();
-
     let payload = Payload::from([(
         PayloadKey::Source.to_string(),
         PayloadType::Pubkey(wallet.pubkey()),
@@ -72,9 +72,69 @@ async fn is_wallet() {
         .unwrap()
         .instruction();
 
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // --------------------------------
+    // Validate fail off-curve
+    // --------------------------------
+    // A PDA is off-curve and cannot sign, even though it is owned by the System Program.
+    let (pda, _bump) = Pubkey::find_program_address(&[b"is_wallet"], &lpl_token_auth_rules::ID);
+
+    let payload = Payload::from([(PayloadKey::Source.to_string(), PayloadType::Pubkey(pda))]);
+
+    // Create a `validate` instruction.
+    let validate_ix = ValidateBuilder::new()
+        .rule_set_pda(rule_set_addr)
+        .mint(mint)
+        .additional_rule_accounts(vec![AccountMeta::new_readonly(pda, false)])
+        .build(ValidateArgs::V1 {
+            operation: Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            payload,
+            update_rule_state: false,
+            rule_set_revision: None,
+        })
+        .unwrap()
+        .instruction();
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::IsWalletCheckFailed);
+
+    // --------------------------------
+    // Validate fail program-owned
+    // --------------------------------
+    // The `RuleSet` PDA is owned by the lpl-token-auth-rules program.
+    let payload = Payload::from([(
+        PayloadKey::Source.to_string(),
+        PayloadType::Pubkey(rule_set_addr),
+    )]);
+
+    // Create a `validate` instruction.
+    let validate_ix = ValidateBuilder::new()
+        .rule_set_pda(rule_set_addr)
+        .mint(mint)
+        .additional_rule_accounts(vec![AccountMeta::new_readonly(rule_set_addr, false)])
+        .build(ValidateArgs::V1 {
+            operation: Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            payload,
+            update_rule_state: false,
+            rule_set_revision: None,
+        })
+        .unwrap()
+        .instruction();
+
     // Fail to validate Transfer operation.
     let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
 
     // Check that error is what we expect.
-    assert_custom_error!(err, RuleSetError::NotImplemented);
+    assert_custom_error!(err, RuleSetError::IsWalletCheckFailed);
 }
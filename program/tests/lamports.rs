@@ -0,0 +1,163 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{AmountTarget, CompareOp, Rule, RuleSetV1},
+};
+use solana_program::native_token::LAMPORTS_PER_SOL;
+use solana_program_test::tokio;
+use solana_sdk::{instruction::AccountMeta, signature::Signer, signer::keypair::Keypair};
+use utils::{create_validate_ix, program_test, Operation, PayloadKey};
+
+#[tokio::test]
+async fn test_lamports() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring the destination to hold at least one SOL.
+    let rule = Rule::Lamports {
+        field: PayloadKey::Destination.to_string(),
+        operator: CompareOp::GtEq,
+        target: AmountTarget::Amount(LAMPORTS_PER_SOL),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Use a new wallet with no lamports as the destination.
+    let empty_wallet = Keypair::new().pubkey();
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(empty_wallet),
+        )]),
+        vec![AccountMeta::new_readonly(empty_wallet, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::LamportsCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Use the funded payer as the destination.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(context.payer.pubkey()),
+        )]),
+        vec![AccountMeta::new_readonly(context.payer.pubkey(), false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_rent_exempt() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring the destination to be rent-exempt.
+    let rule = Rule::RentExempt {
+        field: PayloadKey::Destination.to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Use a new wallet with no lamports as the destination.
+    let empty_wallet = Keypair::new().pubkey();
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(empty_wallet),
+        )]),
+        vec![AccountMeta::new_readonly(empty_wallet, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::RentExemptCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Use the RuleSet, which is rent-exempt, as the destination.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(rule_set_addr),
+        )]),
+        vec![AccountMeta::new_readonly(rule_set_addr, false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
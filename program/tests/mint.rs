@@ -0,0 +1,278 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{
+        AmountTarget, CompareOp, MintAccountField, MintAmountField, PubkeyTarget, Rule, RuleSetV1,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use utils::{
+    create_associated_token_account, create_mint, create_test_merkle_tree_from_one_leaf,
+    create_validate_ix, mint_to, program_test, Operation, PayloadKey,
+};
+
+#[tokio::test]
+async fn test_mint_match() {
+    let mut context = program_test().start_with_context().await;
+
+    // Create a Keypair to simulate a token mint address in the collection.
+    let mint = Keypair::new().pubkey();
+
+    let tree = create_test_merkle_tree_from_one_leaf(&mint, 4);
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing only the mints in the collection tree.
+    let rule = Rule::MintMatch {
+        mint: PubkeyTarget::Tree {
+            root: tree.root,
+            proof_field: PayloadKey::SourceProof.to_string(),
+        },
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition, with the proof for the
+    // collection mint.
+    let payload = Payload::from([(
+        PayloadKey::SourceProof.to_string(),
+        PayloadType::MerkleProof(tree.proof),
+    )]);
+
+    // Create a `validate` instruction for a mint NOT in the collection.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        Keypair::new().pubkey(),
+        payload.clone(),
+        vec![],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MintMatchCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Create a `validate` instruction for the collection mint.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_mint_account_match() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring the mint to be frozen by the collection authority.
+    let collection_authority = Keypair::new().pubkey();
+    let rule = Rule::MintAccountMatch {
+        mint_account_field: MintAccountField::FreezeAuthority,
+        target: PubkeyTarget::Pubkey(collection_authority),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a mint with no freeze authority and one frozen by the collection authority.
+    let payer_pubkey = context.payer.pubkey();
+    let other_mint = Keypair::new();
+    create_mint(&mut context, &other_mint, &payer_pubkey, None, 0)
+        .await
+        .unwrap();
+
+    let mint = Keypair::new();
+    create_mint(
+        &mut context,
+        &mint,
+        &payer_pubkey,
+        Some(&collection_authority),
+        0,
+    )
+    .await
+    .unwrap();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Create a `validate` instruction for the mint with no freeze authority.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        other_mint.pubkey(),
+        Payload::default(),
+        vec![],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MintAccountMatchCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Create a `validate` instruction for the mint frozen by the collection authority.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        Payload::default(),
+        vec![],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_mint_account_amount() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing only non-fungible mints, with zero decimals and a supply of one.
+    let rule = Rule::All {
+        rules: vec![
+            Rule::MintAccountAmount {
+                mint_amount_field: MintAmountField::Decimals,
+                operator: CompareOp::Eq,
+                target: AmountTarget::Amount(0),
+            },
+            Rule::MintAccountAmount {
+                mint_amount_field: MintAmountField::Supply,
+                operator: CompareOp::Eq,
+                target: AmountTarget::Amount(1),
+            },
+        ],
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a mint and a token account to mint into.
+    let manager = Keypair::new();
+    let mint = Keypair::new();
+    create_mint(&mut context, &mint, &manager.pubkey(), None, 0)
+        .await
+        .unwrap();
+
+    let holder = Keypair::new();
+    let token_account = create_associated_token_account(&mut context, &holder, &mint.pubkey())
+        .await
+        .unwrap();
+
+    mint_to(&mut context, &mint.pubkey(), &token_account, &manager, 2)
+        .await
+        .unwrap();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Create a `validate` instruction for the mint with a supply of two.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        Payload::default(),
+        vec![],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MintAccountAmountCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Create a non-fungible mint with a supply of one.
+    let mint = Keypair::new();
+    create_mint(&mut context, &mint, &manager.pubkey(), None, 0)
+        .await
+        .unwrap();
+
+    let token_account = create_associated_token_account(&mut context, &holder, &mint.pubkey())
+        .await
+        .unwrap();
+
+    mint_to(&mut context, &mint.pubkey(), &token_account, &manager, 1)
+        .await
+        .unwrap();
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        Payload::default(),
+        vec![],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
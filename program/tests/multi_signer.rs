@@ -0,0 +1,243 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{Rule, RuleSetV1, SignerSet},
+};
+use solana_program_test::tokio;
+use solana_sdk::{instruction::AccountMeta, signature::Signer, signer::keypair::Keypair};
+use utils::{
+    create_test_merkle_tree_from_one_leaf, create_validate_ix, program_test, try_create_rule_set,
+    Operation, PayloadKey,
+};
+
+#[tokio::test]
+async fn test_multi_signer_list() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring two of three signers.
+    let first_signer = Keypair::new();
+    let second_signer = Keypair::new();
+    let third_signer = Keypair::new();
+
+    let rule = Rule::MultiSigner {
+        signers: SignerSet::List(vec![
+            first_signer.pubkey(),
+            second_signer.pubkey(),
+            third_signer.pubkey(),
+        ]),
+        required: 2,
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // Create a `validate` instruction with only one signer and one non-signing account.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::default(),
+        vec![
+            AccountMeta::new_readonly(first_signer.pubkey(), true),
+            AccountMeta::new_readonly(second_signer.pubkey(), false),
+        ],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&first_signer], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MultiSignerCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Create a `validate` instruction with two signers.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::default(),
+        vec![
+            AccountMeta::new_readonly(first_signer.pubkey(), true),
+            AccountMeta::new_readonly(third_signer.pubkey(), true),
+        ],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(
+        &mut context,
+        validate_ix,
+        vec![&first_signer, &third_signer],
+        None
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_multi_signer_tree() {
+    let mut context = program_test().start_with_context().await;
+
+    // Create a signer that is a member of a Merkle tree.
+    let signer = Keypair::new();
+    let tree = create_test_merkle_tree_from_one_leaf(&signer.pubkey(), 4);
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule.
+    let rule = Rule::MultiSigner {
+        signers: SignerSet::Tree {
+            root: tree.root,
+            fields: vec![(
+                PayloadKey::Authority.to_string(),
+                PayloadKey::AuthorityProof.to_string(),
+            )],
+        },
+        required: 1,
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with an INCORRECT proof.
+    let incorrect_tree = create_test_merkle_tree_from_one_leaf(&signer.pubkey(), 4);
+
+    let payload = Payload::from([
+        (
+            PayloadKey::Authority.to_string(),
+            PayloadType::Pubkey(signer.pubkey()),
+        ),
+        (
+            PayloadKey::AuthorityProof.to_string(),
+            PayloadType::MerkleProof(incorrect_tree.proof),
+        ),
+    ]);
+
+    // Create a `validate` instruction with the signer.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload,
+        vec![AccountMeta::new_readonly(signer.pubkey(), true)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![&signer], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MultiSignerCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with the CORRECT proof.
+    let payload = Payload::from([
+        (
+            PayloadKey::Authority.to_string(),
+            PayloadType::Pubkey(signer.pubkey()),
+        ),
+        (
+            PayloadKey::AuthorityProof.to_string(),
+            PayloadType::MerkleProof(tree.proof),
+        ),
+    ]);
+
+    // Create a `validate` instruction with the signer.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload,
+        vec![AccountMeta::new_readonly(signer.pubkey(), true)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&signer], None).await;
+}
+
+#[tokio::test]
+async fn create_multi_signer_unreachable_fails() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring more signers than it lists.
+    let rule = Rule::MultiSigner {
+        signers: SignerSet::List(vec![Keypair::new().pubkey(), Keypair::new().pubkey()]),
+        required: 3,
+    };
+
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    // --------------------------------
+    // Fail on-chain creation
+    // --------------------------------
+    let err = try_create_rule_set(&mut context, rule_set, "test rule_set".to_string())
+        .await
+        .expect_err("Creation should fail");
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MultiSignerUnreachable);
+}
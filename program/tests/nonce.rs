@@ -0,0 +1,633 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use borsh::BorshDeserialize;
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    instruction::{builders::ValidateBuilder, InstructionBuilder, ValidateArgs},
+    payload::{Payload, PayloadType},
+    pda::find_rule_set_state_address,
+    state::{Rule, RuleSetStateAccount, RuleSetV1, RuleState},
+};
+use solana_program::{clock::Clock, pubkey::Pubkey};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use std::time::{SystemTime, UNIX_EPOCH};
+use utils::{
+    advance_clock, create_validate_ix, program_test, Operation, PayloadKey, RuleStateAccounts,
+};
+
+const EXPIRY_FIELD: &str = "Expiry";
+
+// A compute budget that makes a replayed transaction differ from the original one.
+const REPLAY_COMPUTE_BUDGET: u32 = 400_000;
+
+#[tokio::test]
+async fn test_nonce() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing each nonce to be used once, for up to a day.
+    let rule_authority = Keypair::new();
+    let rule = Rule::Nonce {
+        authority: rule_authority.pubkey(),
+        field: PayloadKey::Nonce.to_string(),
+        expiry_field: EXPIRY_FIELD.to_string(),
+        max_lifetime: 24 * 60 * 60,
+    };
+
+    // Expire the nonces in an hour.
+    let expiry = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 60 * 60;
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    // --------------------------------
+    // Validate fail missing nonce
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::default(),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MissingPayloadValue);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Use a hash as the nonce.
+    let nonce = Payload::from([
+        (
+            PayloadKey::Nonce.to_string(),
+            PayloadType::Pubkey(Pubkey::new_unique()),
+        ),
+        (EXPIRY_FIELD.to_string(), PayloadType::Number(expiry)),
+    ]);
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        nonce.clone(),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // --------------------------------
+    // Validate fail replayed nonce
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        nonce.clone(),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation, replaying the nonce in another transaction.
+    let err = process_failing_validate_ix!(
+        &mut context,
+        validate_ix,
+        vec![&rule_authority],
+        Some(REPLAY_COMPUTE_BUDGET)
+    )
+    .await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::NonceCheckFailed);
+
+    // --------------------------------
+    // Validate fail without updating state
+    // --------------------------------
+    // The nonce would not be consumed, so the rule cannot pass.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([
+            (PayloadKey::Nonce.to_string(), PayloadType::Number(2)),
+            (EXPIRY_FIELD.to_string(), PayloadType::Number(expiry)),
+        ]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: false,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::NonceCheckFailed);
+
+    // --------------------------------
+    // Validate pass new nonce
+    // --------------------------------
+    // Use a number as the nonce.
+    let nonce = Payload::from([
+        (PayloadKey::Nonce.to_string(), PayloadType::Number(1)),
+        (EXPIRY_FIELD.to_string(), PayloadType::Number(expiry)),
+    ]);
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        nonce,
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // --------------------------------
+    // Validate fail expired nonce
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([
+            (PayloadKey::Nonce.to_string(), PayloadType::Number(3)),
+            (EXPIRY_FIELD.to_string(), PayloadType::Number(1)),
+        ]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::NonceCheckFailed);
+
+    // --------------------------------
+    // Validate fail nonce outliving the maximum lifetime
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([
+            (PayloadKey::Nonce.to_string(), PayloadType::Number(3)),
+            (
+                EXPIRY_FIELD.to_string(),
+                PayloadType::Number(expiry + 7 * 24 * 60 * 60),
+            ),
+        ]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::NonceCheckFailed);
+}
+
+#[tokio::test]
+async fn test_nonce_shared_across_operations() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing each nonce to be used once, for up to a day.
+    let rule_authority = Keypair::new();
+    let rule = Rule::Nonce {
+        authority: rule_authority.pubkey(),
+        field: PayloadKey::Nonce.to_string(),
+        expiry_field: EXPIRY_FIELD.to_string(),
+        max_lifetime: 24 * 60 * 60,
+    };
+
+    // Create a RuleSet using the Rule for two operations.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    for scenario in [
+        utils::TransferScenario::Holder,
+        utils::TransferScenario::TransferDelegate,
+    ] {
+        rule_set
+            .add(Operation::Transfer { scenario }.to_string(), rule.clone())
+            .unwrap();
+    }
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    let expiry = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 60 * 60;
+    let nonce = Payload::from([
+        (PayloadKey::Nonce.to_string(), PayloadType::Number(1)),
+        (EXPIRY_FIELD.to_string(), PayloadType::Number(expiry)),
+    ]);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        nonce.clone(),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // --------------------------------
+    // Validate fail replayed nonce for another operation
+    // --------------------------------
+    // The nonces are consumed per mint, not per operation.
+    let validate_ix = ValidateBuilder::new()
+        .rule_set_pda(rule_set_addr)
+        .mint(mint)
+        .payer(context.payer.pubkey())
+        .rule_authority(rule_authority.pubkey())
+        .rule_set_state_pda(rule_set_state_addr)
+        .additional_rule_accounts(vec![])
+        .build(ValidateArgs::V1 {
+            operation: Operation::Transfer {
+                scenario: utils::TransferScenario::TransferDelegate,
+            }
+            .to_string(),
+            payload: nonce,
+            update_rule_state: true,
+            rule_set_revision: None,
+        })
+        .unwrap()
+        .instruction();
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::NonceCheckFailed);
+}
+
+#[tokio::test]
+async fn test_nonce_pruned_after_expiry() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing each nonce to be used once, for up to a day.
+    let rule_authority = Keypair::new();
+    let rule = Rule::Nonce {
+        authority: rule_authority.pubkey(),
+        field: PayloadKey::Nonce.to_string(),
+        expiry_field: EXPIRY_FIELD.to_string(),
+        max_lifetime: 24 * 60 * 60,
+    };
+
+    // Create a RuleSet.
+    let operation = Operation::Transfer {
+        scenario: utils::TransferScenario::Holder,
+    }
+    .to_string();
+
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set.add(operation.clone(), rule.clone()).unwrap();
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    // --------------------------------
+    // Validate pass two nonces
+    // --------------------------------
+    // Expire the first nonce in a minute and the second one in two minutes.
+    let now = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp as u64;
+
+    for (nonce, expiry) in [(1, now + 60), (2, now + 2 * 60)] {
+        let validate_ix = create_validate_ix(
+            rule_set_addr,
+            mint,
+            Payload::from([
+                (PayloadKey::Nonce.to_string(), PayloadType::Number(nonce)),
+                (EXPIRY_FIELD.to_string(), PayloadType::Number(expiry)),
+            ]),
+            vec![],
+            Some(RuleStateAccounts {
+                payer: context.payer.pubkey(),
+                rule_authority: rule_authority.pubkey(),
+                rule_set_state_pda: rule_set_state_addr,
+                update_rule_state: true,
+            }),
+        );
+
+        // Validate Transfer operation.
+        process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+    }
+
+    assert_eq!(
+        get_consumed_nonces(&mut context, rule_set_state_addr, &rule, &operation).await,
+        vec![(1, now + 60), (2, now + 2 * 60)]
+    );
+
+    // --------------------------------
+    // Validate pass after the first nonce expired
+    // --------------------------------
+    // Move the clock to the expiry of the second nonce.
+    advance_clock(&mut context, 2 * 60).await;
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([
+            (PayloadKey::Nonce.to_string(), PayloadType::Number(3)),
+            (EXPIRY_FIELD.to_string(), PayloadType::Number(now + 3 * 60)),
+        ]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that only the expired nonce was pruned.
+    assert_eq!(
+        get_consumed_nonces(&mut context, rule_set_state_addr, &rule, &operation).await,
+        vec![(2, now + 2 * 60), (3, now + 3 * 60)]
+    );
+
+    // --------------------------------
+    // Validate fail replayed nonce at its expiry
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([
+            (PayloadKey::Nonce.to_string(), PayloadType::Number(2)),
+            (EXPIRY_FIELD.to_string(), PayloadType::Number(now + 2 * 60)),
+        ]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::NonceCheckFailed);
+}
+
+#[tokio::test]
+async fn test_nonce_kept_after_update() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing each nonce to be used once, for up to a day.
+    let rule_authority = Keypair::new();
+    let operation = Operation::Transfer {
+        scenario: utils::TransferScenario::Holder,
+    }
+    .to_string();
+
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            operation.clone(),
+            Rule::Nonce {
+                authority: rule_authority.pubkey(),
+                field: PayloadKey::Nonce.to_string(),
+                expiry_field: EXPIRY_FIELD.to_string(),
+                max_lifetime: 24 * 60 * 60,
+            },
+        )
+        .unwrap();
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    let now = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp as u64;
+    let nonce = Payload::from([
+        (PayloadKey::Nonce.to_string(), PayloadType::Number(1)),
+        (EXPIRY_FIELD.to_string(), PayloadType::Number(now + 60 * 60)),
+    ]);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        nonce.clone(),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // --------------------------------
+    // Update RuleSet
+    // --------------------------------
+    // Lower the maximum lifetime to two hours.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            operation,
+            Rule::Nonce {
+                authority: rule_authority.pubkey(),
+                field: PayloadKey::Nonce.to_string(),
+                expiry_field: EXPIRY_FIELD.to_string(),
+                max_lifetime: 2 * 60 * 60,
+            },
+        )
+        .unwrap();
+
+    // Put the updated RuleSet on chain.
+    create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // --------------------------------
+    // Validate fail replayed nonce
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        nonce,
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation, replaying the nonce in another transaction.
+    let err = process_failing_validate_ix!(
+        &mut context,
+        validate_ix,
+        vec![&rule_authority],
+        Some(REPLAY_COMPUTE_BUDGET)
+    )
+    .await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::NonceCheckFailed);
+}
+
+// Get the consumed nonces, as `Number` nonces and their expiry.
+async fn get_consumed_nonces(
+    context: &mut ProgramTestContext,
+    rule_set_state_addr: Pubkey,
+    rule: &Rule,
+    operation: &str,
+) -> Vec<(u64, u64)> {
+    let rule_set_state_account = context
+        .banks_client
+        .get_account(rule_set_state_addr)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let state_account = RuleSetStateAccount::try_from_slice(&rule_set_state_account.data).unwrap();
+
+    match state_account
+        .states
+        .get(&RuleSetStateAccount::state_key(rule, operation).unwrap())
+    {
+        Some(RuleState::Nonce { consumed }) => consumed
+            .iter()
+            .map(|consumed_nonce| {
+                let mut nonce = [0u8; 8];
+                nonce.copy_from_slice(&consumed_nonce.nonce[..8]);
+                (u64::from_le_bytes(nonce), consumed_nonce.expiry as u64)
+            })
+            .collect(),
+        _ => panic!("Missing Nonce state"),
+    }
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{Rule, RuleSetV1, SeedTemplate},
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::tokio;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use utils::{create_validate_ix, program_test, Operation, PayloadKey};
+
+#[tokio::test]
+async fn test_pda_template_match() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring the destination to be an escrow PDA of the mint, the authority,
+    // and the amount.
+    let escrow_program = Keypair::new().pubkey();
+    let rule = Rule::PDATemplateMatch {
+        program: Some(escrow_program),
+        pda_field: PayloadKey::Destination.to_string(),
+        seeds: vec![
+            SeedTemplate::Bytes("escrow".as_bytes().to_vec()),
+            SeedTemplate::Mint,
+            SeedTemplate::Pubkey(PayloadKey::Authority.to_string()),
+            SeedTemplate::Amount(PayloadKey::Amount.to_string()),
+        ],
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let authority = Keypair::new().pubkey();
+    let (escrow, _bump) = Pubkey::find_program_address(
+        &[
+            "escrow".as_bytes(),
+            mint.as_ref(),
+            authority.as_ref(),
+            &1u64.to_le_bytes(),
+        ],
+        &escrow_program,
+    );
+
+    // --------------------------------
+    // Validate fail missing value
+    // --------------------------------
+    // Store a payload of data WITHOUT the authority used in the seeds.
+    let payload = Payload::from([
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(escrow),
+        ),
+        (PayloadKey::Amount.to_string(), PayloadType::Number(1)),
+    ]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MissingPayloadValue);
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store a payload of data with an amount that does NOT match the escrow seeds.
+    let payload = Payload::from([
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(escrow),
+        ),
+        (
+            PayloadKey::Authority.to_string(),
+            PayloadType::Pubkey(authority),
+        ),
+        (PayloadKey::Amount.to_string(), PayloadType::Number(2)),
+    ]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::PDAMatchCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store a payload of data matching the escrow seeds.
+    let payload = Payload::from([
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(escrow),
+        ),
+        (
+            PayloadKey::Authority.to_string(),
+            PayloadType::Pubkey(authority),
+        ),
+        (PayloadKey::Amount.to_string(), PayloadType::Number(1)),
+    ]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
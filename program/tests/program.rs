@@ -0,0 +1,439 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{PubkeyTarget, Rule, RuleSetV1},
+};
+use solana_program::{
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    pubkey::Pubkey,
+    system_program,
+};
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::{
+    account::{AccountSharedData, WritableAccount},
+    instruction::AccountMeta,
+    signature::Signer,
+    signer::keypair::Keypair,
+};
+use utils::{create_validate_ix, program_test, Operation, PayloadKey};
+
+#[tokio::test]
+async fn test_is_program() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring the destination to be a program.
+    let rule = Rule::IsProgram {
+        field: PayloadKey::Destination.to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Use the payer wallet as the destination.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(context.payer.pubkey()),
+        )]),
+        vec![AccountMeta::new_readonly(context.payer.pubkey(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::IsProgramCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Use lpl-token-auth-rules as the destination.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(lpl_token_auth_rules::ID),
+        )]),
+        vec![AccountMeta::new_readonly(lpl_token_auth_rules::ID, false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_is_program_closed() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule requiring the destination to be a program.
+    let rule = Rule::IsProgram {
+        field: PayloadKey::Destination.to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (program, program_data) = set_upgradeable_program(&mut context, None);
+    let payload = Payload::from([(
+        PayloadKey::Destination.to_string(),
+        PayloadType::Pubkey(program),
+    )]);
+
+    // --------------------------------
+    // Validate fail missing program data
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload.clone(),
+        vec![AccountMeta::new_readonly(program, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MissingAccount);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload.clone(),
+        vec![
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(program_data, false),
+        ],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // --------------------------------
+    // Validate fail closed program
+    // --------------------------------
+    // Closing the program empties its program data account, which the System Program then owns.
+    let closed_program_data_account = AccountSharedData::new(1_000_000_000, 0, &system_program::ID);
+    context.set_account(&program_data, &closed_program_data_account);
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload,
+        vec![
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(program_data, false),
+        ],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::IsProgramCheckFailed);
+}
+
+#[tokio::test]
+async fn test_program_upgrade_authority() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing only programs upgradeable by a multisig.
+    let multisig = Keypair::new().pubkey();
+    let rule = Rule::ProgramUpgradeAuthority {
+        field: PayloadKey::Destination.to_string(),
+        authority: Some(PubkeyTarget::List(vec![Keypair::new().pubkey(), multisig])),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // Create a program upgradeable by another authority and one upgradeable by the multisig.
+    let (other_program, other_program_data) =
+        set_upgradeable_program(&mut context, Some(Keypair::new().pubkey()));
+    let (program, program_data) = set_upgradeable_program(&mut context, Some(multisig));
+
+    // --------------------------------
+    // Validate fail missing program data
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(program),
+        )]),
+        vec![AccountMeta::new_readonly(program, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MissingAccount);
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(other_program),
+        )]),
+        vec![
+            AccountMeta::new_readonly(other_program, false),
+            AccountMeta::new_readonly(other_program_data, false),
+        ],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::ProgramUpgradeAuthorityCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(program),
+        )]),
+        vec![
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(program_data, false),
+        ],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_program_immutable() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing only immutable programs.
+    let rule = Rule::ProgramUpgradeAuthority {
+        field: PayloadKey::Destination.to_string(),
+        authority: None,
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // Create an upgradeable program and one whose upgrade authority has been removed.
+    let (upgradeable_program, upgradeable_program_data) =
+        set_upgradeable_program(&mut context, Some(Keypair::new().pubkey()));
+    let (program, program_data) = set_upgradeable_program(&mut context, None);
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(upgradeable_program),
+        )]),
+        vec![
+            AccountMeta::new_readonly(upgradeable_program, false),
+            AccountMeta::new_readonly(upgradeable_program_data, false),
+        ],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::ProgramUpgradeAuthorityCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(program),
+        )]),
+        vec![
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(program_data, false),
+        ],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // --------------------------------
+    // Validate fail closed program
+    // --------------------------------
+    // A closed program has no upgrade authority, but it is not an immutable program either.
+    let closed_program_data_account = AccountSharedData::new(1_000_000_000, 0, &system_program::ID);
+    context.set_account(&program_data, &closed_program_data_account);
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(program),
+        )]),
+        vec![
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(program_data, false),
+        ],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::ProgramUpgradeAuthorityCheckFailed);
+}
+
+// Store a program account and its program data account for the upgradeable BPF loader.
+fn set_upgradeable_program(
+    context: &mut ProgramTestContext,
+    upgrade_authority_address: Option<Pubkey>,
+) -> (Pubkey, Pubkey) {
+    let program = Keypair::new().pubkey();
+    let (program_data, _bump) =
+        Pubkey::find_program_address(&[program.as_ref()], &bpf_loader_upgradeable::ID);
+
+    let program_state = UpgradeableLoaderState::Program {
+        programdata_address: program_data,
+    };
+    let mut program_account =
+        AccountSharedData::new_data(1_000_000_000, &program_state, &bpf_loader_upgradeable::ID)
+            .unwrap();
+    program_account.set_executable(true);
+    context.set_account(&program, &program_account);
+
+    let program_data_state = UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address,
+    };
+    let program_data_account = AccountSharedData::new_data_with_space(
+        1_000_000_000,
+        &program_data_state,
+        UpgradeableLoaderState::size_of_programdata_metadata(),
+        &bpf_loader_upgradeable::ID,
+    )
+    .unwrap();
+    context.set_account(&program_data, &program_data_account);
+
+    (program, program_data)
+}
@@ -0,0 +1,504 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use borsh::BorshDeserialize;
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    pda::find_rule_set_state_address,
+    state::{Key, Rule, RuleSetStateAccount, RuleSetV1, RuleState},
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use utils::{
+    advance_clock, create_validate_ix, program_test, try_create_rule_set, Operation, PayloadKey,
+    RuleStateAccounts,
+};
+
+#[tokio::test]
+async fn test_quota() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing no more than 10 units per day.
+    let rule_authority = Keypair::new();
+    let quota = Rule::Quota {
+        name: "daily".to_string(),
+        authority: rule_authority.pubkey(),
+        field: PayloadKey::Amount.to_string(),
+        cap: 10,
+        period: 24 * 60 * 60,
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            quota.clone(),
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    // --------------------------------
+    // Validate wrong authority
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(6))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: context.payer.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::RuleAuthorityIsNotSigner);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(6))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that the state PDA was created and the amount stored.
+    assert_eq!(
+        get_quota_amount(&mut context, rule_set_state_addr, &quota).await,
+        6
+    );
+
+    // --------------------------------
+    // Validate fail over cap
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(5))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::QuotaCheckFailed);
+
+    // --------------------------------
+    // Validate pass without updating state
+    // --------------------------------
+    // The cap is not checked when state is not being updated.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(5))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: false,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // --------------------------------
+    // Validate pass up to cap
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(4))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that the total for the period was updated.
+    assert_eq!(
+        get_quota_amount(&mut context, rule_set_state_addr, &quota).await,
+        10
+    );
+
+    // --------------------------------
+    // Validate pass after period
+    // --------------------------------
+    // Move the clock past the period, which resets the total.
+    advance_clock(&mut context, 24 * 60 * 60).await;
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(6))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that the total started over.
+    assert_eq!(
+        get_quota_amount(&mut context, rule_set_state_addr, &quota).await,
+        6
+    );
+}
+
+#[tokio::test]
+async fn test_two_quotas() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create two Rules with different caps, each of which must keep its own total.
+    let rule_authority = Keypair::new();
+    let daily_quota = Rule::Quota {
+        name: "daily".to_string(),
+        authority: rule_authority.pubkey(),
+        field: PayloadKey::Amount.to_string(),
+        cap: 10,
+        period: 24 * 60 * 60,
+    };
+    let hourly_quota = Rule::Quota {
+        name: "hourly".to_string(),
+        authority: rule_authority.pubkey(),
+        field: PayloadKey::Amount.to_string(),
+        cap: 5,
+        period: 60 * 60,
+    };
+
+    let rule = Rule::All {
+        rules: vec![daily_quota.clone(), hourly_quota.clone()],
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(4))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that each quota stored its own total.
+    assert_eq!(
+        get_quota_amount(&mut context, rule_set_state_addr, &daily_quota).await,
+        4
+    );
+    assert_eq!(
+        get_quota_amount(&mut context, rule_set_state_addr, &hourly_quota).await,
+        4
+    );
+
+    // --------------------------------
+    // Validate fail over the smaller cap
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(2))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::QuotaCheckFailed);
+}
+
+#[tokio::test]
+async fn test_quota_pruned_after_update() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing no more than 10 units per day.
+    let rule_authority = Keypair::new();
+    let daily_quota = Rule::Quota {
+        name: "daily".to_string(),
+        authority: rule_authority.pubkey(),
+        field: PayloadKey::Amount.to_string(),
+        cap: 10,
+        period: 24 * 60 * 60,
+    };
+
+    let operation = Operation::Transfer {
+        scenario: utils::TransferScenario::Holder,
+    }
+    .to_string();
+
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(operation.clone(), daily_quota.clone())
+        .unwrap();
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(6))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // --------------------------------
+    // Update RuleSet
+    // --------------------------------
+    // Replace the daily quota with a weekly quota.
+    let weekly_quota = Rule::Quota {
+        name: "weekly".to_string(),
+        authority: rule_authority.pubkey(),
+        field: PayloadKey::Amount.to_string(),
+        cap: 50,
+        period: 7 * 24 * 60 * 60,
+    };
+
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(operation.clone(), weekly_quota.clone())
+        .unwrap();
+
+    // Put the updated RuleSet on chain.
+    create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(4))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that the total of the removed quota was dropped.
+    assert_eq!(
+        get_quota_amount(&mut context, rule_set_state_addr, &weekly_quota).await,
+        4
+    );
+
+    let rule_set_state_account = context
+        .banks_client
+        .get_account(rule_set_state_addr)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let state_account = RuleSetStateAccount::try_from_slice(&rule_set_state_account.data).unwrap();
+    assert!(!state_account
+        .states
+        .contains_key(&RuleSetStateAccount::state_key(&daily_quota, &operation).unwrap()));
+    assert_eq!(state_account.states.len(), 1);
+}
+
+#[tokio::test]
+async fn create_duplicate_quotas_fails() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create two Rules with the same name, which would share their total.
+    let rule_authority = Keypair::new();
+    let rule = Rule::All {
+        rules: vec![
+            Rule::Quota {
+                name: "daily".to_string(),
+                authority: rule_authority.pubkey(),
+                field: PayloadKey::Amount.to_string(),
+                cap: 10,
+                period: 24 * 60 * 60,
+            },
+            Rule::Quota {
+                name: "daily".to_string(),
+                authority: rule_authority.pubkey(),
+                field: PayloadKey::Amount.to_string(),
+                cap: 5,
+                period: 24 * 60 * 60,
+            },
+        ],
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    // --------------------------------
+    // Fail on-chain creation
+    // --------------------------------
+    let err = try_create_rule_set(&mut context, rule_set, "test rule_set".to_string())
+        .await
+        .expect_err("Creation should fail");
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::StatefulRuleDuplicated);
+}
+
+async fn get_quota_amount(
+    context: &mut ProgramTestContext,
+    rule_set_state_addr: Pubkey,
+    quota: &Rule,
+) -> u64 {
+    let rule_set_state_account = context
+        .banks_client
+        .get_account(rule_set_state_addr)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(rule_set_state_account.owner, lpl_token_auth_rules::ID);
+
+    let state_account = RuleSetStateAccount::try_from_slice(&rule_set_state_account.data).unwrap();
+    assert_eq!(state_account.key, Key::RuleSetState);
+
+    let state_key = RuleSetStateAccount::state_key(
+        quota,
+        &Operation::Transfer {
+            scenario: utils::TransferScenario::Holder,
+        }
+        .to_string(),
+    )
+    .unwrap();
+
+    match state_account.states.get(&state_key) {
+        Some(RuleState::Quota { amount, .. }) => *amount,
+        _ => panic!("Missing Quota state"),
+    }
+}
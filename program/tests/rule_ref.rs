@@ -0,0 +1,195 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    instruction::{builders::ValidateBuilder, InstructionBuilder, ValidateArgs},
+    payload::{Payload, PayloadType},
+    state::{CompareOp, Rule, RuleSetV2},
+};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use utils::{program_test, try_create_rule_set, Operation, PayloadKey};
+
+#[tokio::test]
+async fn test_rule_ref() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a RuleSet where two operations share the same amount limit definition.
+    let mut rule_set = RuleSetV2::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add_definition(
+            "amount limit".to_string(),
+            Rule::Amount {
+                amount: 5,
+                operator: CompareOp::Lt,
+                field: PayloadKey::Amount.to_string(),
+            },
+        )
+        .unwrap();
+
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            Rule::Ref("amount limit".to_string()),
+        )
+        .unwrap();
+
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::TransferDelegate,
+            }
+            .to_string(),
+            Rule::All {
+                rules: vec![Rule::Pass, Rule::Ref("amount limit".to_string())],
+            },
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    for scenario in [
+        utils::TransferScenario::Holder,
+        utils::TransferScenario::TransferDelegate,
+    ] {
+        // --------------------------------
+        // Validate fail
+        // --------------------------------
+        // Store a payload of data with the WRONG amount.
+        let payload = Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(5))]);
+
+        // Create a `validate` instruction.
+        let validate_ix = ValidateBuilder::new()
+            .rule_set_pda(rule_set_addr)
+            .mint(mint)
+            .additional_rule_accounts(vec![])
+            .build(ValidateArgs::V1 {
+                operation: Operation::Transfer {
+                    scenario: scenario.clone(),
+                }
+                .to_string(),
+                payload,
+                update_rule_state: false,
+                rule_set_revision: None,
+            })
+            .unwrap()
+            .instruction();
+
+        // Fail to validate Transfer operation.
+        let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+        // Check that error is what we expect.
+        assert_custom_error!(err, RuleSetError::AmountCheckFailed);
+
+        // --------------------------------
+        // Validate pass
+        // --------------------------------
+        // Store a payload of data with the correct amount.
+        let payload = Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(4))]);
+
+        // Create a `validate` instruction.
+        let validate_ix = ValidateBuilder::new()
+            .rule_set_pda(rule_set_addr)
+            .mint(mint)
+            .additional_rule_accounts(vec![])
+            .build(ValidateArgs::V1 {
+                operation: Operation::Transfer { scenario }.to_string(),
+                payload,
+                update_rule_state: false,
+                rule_set_revision: None,
+            })
+            .unwrap()
+            .instruction();
+
+        // Validate Transfer operation.
+        process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+    }
+}
+
+#[tokio::test]
+async fn create_rule_ref_not_found_fails() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a RuleSet referencing a definition that does not exist.
+    let mut rule_set = RuleSetV2::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            Rule::Ref("missing".to_string()),
+        )
+        .unwrap();
+
+    // --------------------------------
+    // Fail on-chain creation
+    // --------------------------------
+    let err = try_create_rule_set(&mut context, rule_set, "test rule_set".to_string())
+        .await
+        .expect_err("Creation should fail");
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::RuleRefNotFound);
+}
+
+#[tokio::test]
+async fn create_rule_ref_cycle_fails() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a RuleSet with definitions referencing each other.
+    let mut rule_set = RuleSetV2::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add_definition("first".to_string(), Rule::Ref("second".to_string()))
+        .unwrap();
+
+    rule_set
+        .add_definition(
+            "second".to_string(),
+            Rule::Not {
+                rule: Box::new(Rule::Ref("first".to_string())),
+            },
+        )
+        .unwrap();
+
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            Rule::Ref("first".to_string()),
+        )
+        .unwrap();
+
+    // --------------------------------
+    // Fail on-chain creation
+    // --------------------------------
+    let err = try_create_rule_set(&mut context, rule_set, "test rule_set".to_string())
+        .await
+        .expect_err("Creation should fail");
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::RuleRefCycle);
+}
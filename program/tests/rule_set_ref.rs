@@ -0,0 +1,289 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{Rule, RuleSetV1},
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::AccountMeta, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+};
+use utils::{create_validate_ix, program_test, try_create_rule_set, Operation, PayloadKey};
+
+/// Create a shared `RuleSet` allowing transfers to the marketplaces in the list.
+fn create_shared_rule_set(owner: Pubkey, marketplaces: Vec<Pubkey>) -> RuleSetV1 {
+    let rule = Rule::PubkeyListMatch {
+        pubkeys: marketplaces,
+        field: PayloadKey::Destination.to_string(),
+    };
+
+    let mut rule_set = RuleSetV1::new("shared rule_set".to_string(), owner);
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    rule_set
+}
+
+#[tokio::test]
+async fn test_rule_set_ref() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSets
+    // --------------------------------
+    // Put the shared RuleSet on chain.
+    let marketplace_a = Keypair::new().pubkey();
+    let marketplace_b = Keypair::new().pubkey();
+
+    let shared_rule_set = create_shared_rule_set(context.payer.pubkey(), vec![marketplace_a]);
+
+    println!("{:#?}", shared_rule_set);
+
+    let shared_rule_set_addr =
+        create_rule_set_on_chain!(&mut context, shared_rule_set, "shared rule_set".to_string())
+            .await;
+
+    // Create a Rule referencing the latest revision of the shared RuleSet.
+    let rule = Rule::RuleSetRef {
+        owner: context.payer.pubkey(),
+        name: "shared rule_set".to_string(),
+        revision: None,
+        operation: Operation::Transfer {
+            scenario: utils::TransferScenario::Holder,
+        }
+        .to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Rule referencing the first revision of the shared RuleSet.
+    let rule = Rule::RuleSetRef {
+        owner: context.payer.pubkey(),
+        name: "shared rule_set".to_string(),
+        revision: Some(0),
+        operation: Operation::Transfer {
+            scenario: utils::TransferScenario::Holder,
+        }
+        .to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut pinned_rule_set = RuleSetV1::new("pinned rule_set".to_string(), context.payer.pubkey());
+    pinned_rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", pinned_rule_set);
+
+    // Put the RuleSet on chain.
+    let pinned_rule_set_addr =
+        create_rule_set_on_chain!(&mut context, pinned_rule_set, "pinned rule_set".to_string())
+            .await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // Store the payload of data to validate against the rule definition with a marketplace
+    // that is not in the first revision of the shared RuleSet.
+    let payload = Payload::from([(
+        PayloadKey::Destination.to_string(),
+        PayloadType::Pubkey(marketplace_b),
+    )]);
+
+    // --------------------------------
+    // Validate fail missing account
+    // --------------------------------
+    // Create a `validate` instruction WITHOUT the shared RuleSet.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload.clone(), vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MissingAccount);
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Create a `validate` instruction WITH the shared RuleSet.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload.clone(),
+        vec![AccountMeta::new_readonly(shared_rule_set_addr, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.  The referenced rule error is returned as is.
+    assert_custom_error!(err, RuleSetError::PubkeyListMatchCheckFailed);
+
+    // --------------------------------
+    // Update shared RuleSet
+    // --------------------------------
+    // Add the marketplace to the shared RuleSet without touching the referencing RuleSets.
+    let shared_rule_set =
+        create_shared_rule_set(context.payer.pubkey(), vec![marketplace_a, marketplace_b]);
+
+    println!("{:#?}", shared_rule_set);
+
+    create_rule_set_on_chain!(&mut context, shared_rule_set, "shared rule_set".to_string()).await;
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Create a `validate` instruction WITH the shared RuleSet.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload.clone(),
+        vec![AccountMeta::new_readonly(shared_rule_set_addr, false)],
+        None,
+    );
+
+    // Validate Transfer operation since the latest shared RuleSet revision allows it.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // --------------------------------
+    // Validate fail pinned revision
+    // --------------------------------
+    // Create a `validate` instruction for the RuleSet pinned to the first shared revision.
+    let validate_ix = create_validate_ix(
+        pinned_rule_set_addr,
+        mint,
+        payload,
+        vec![AccountMeta::new_readonly(shared_rule_set_addr, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::PubkeyListMatchCheckFailed);
+}
+
+/// Create a `RuleSet` whose Transfer operation references the Transfer operation of another one.
+fn create_referencing_rule_set(owner: Pubkey, name: &str, referenced_name: &str) -> RuleSetV1 {
+    let operation = Operation::Transfer {
+        scenario: utils::TransferScenario::Holder,
+    }
+    .to_string();
+
+    let rule = Rule::RuleSetRef {
+        owner,
+        name: referenced_name.to_string(),
+        revision: None,
+        operation: operation.clone(),
+    };
+
+    let mut rule_set = RuleSetV1::new(name.to_string(), owner);
+    rule_set.add(operation, rule).unwrap();
+
+    rule_set
+}
+
+#[tokio::test]
+async fn create_rule_set_ref_self_fails() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a RuleSet referencing itself.
+    let rule_set =
+        create_referencing_rule_set(context.payer.pubkey(), "test rule_set", "test rule_set");
+
+    // --------------------------------
+    // Fail on-chain creation
+    // --------------------------------
+    let err = try_create_rule_set(&mut context, rule_set, "test rule_set".to_string())
+        .await
+        .expect_err("Creation should fail");
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::RuleSetRefSelf);
+}
+
+#[tokio::test]
+async fn test_rule_set_ref_too_deep() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSets
+    // --------------------------------
+    // Create two RuleSets referencing each other, which are only rejected when validating.
+    let first_rule_set =
+        create_referencing_rule_set(context.payer.pubkey(), "first rule_set", "second rule_set");
+    let first_rule_set_addr =
+        create_rule_set_on_chain!(&mut context, first_rule_set, "first rule_set".to_string()).await;
+
+    let second_rule_set =
+        create_referencing_rule_set(context.payer.pubkey(), "second rule_set", "first rule_set");
+    let second_rule_set_addr =
+        create_rule_set_on_chain!(&mut context, second_rule_set, "second rule_set".to_string())
+            .await;
+
+    // Create a RuleSet referencing the first one.
+    let rule_set =
+        create_referencing_rule_set(context.payer.pubkey(), "test rule_set", "first rule_set");
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::default(),
+        vec![
+            AccountMeta::new_readonly(first_rule_set_addr, false),
+            AccountMeta::new_readonly(second_rule_set_addr, false),
+        ],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::RuleSetRefTooDeep);
+}
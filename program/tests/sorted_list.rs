@@ -0,0 +1,218 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{Rule, RuleSetV1},
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::tokio;
+use solana_sdk::{instruction::AccountMeta, signature::Signer, signer::keypair::Keypair};
+use utils::{
+    create_mint, create_validate_ix, program_test, try_create_rule_set, Operation, PayloadKey,
+};
+
+#[tokio::test]
+async fn test_pubkey_sorted_list_match() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a large sorted allow-list of wallets.
+    let mut pubkeys = (0..200)
+        .map(|_| Keypair::new().pubkey())
+        .collect::<Vec<Pubkey>>();
+    pubkeys.sort();
+
+    let allowed_wallet = pubkeys[117];
+
+    // Create a Rule.
+    let rule = Rule::PubkeySortedListMatch {
+        pubkeys,
+        field: PayloadKey::Destination.to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_big_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string(), None)
+            .await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with a wallet NOT in the
+    // list.
+    let payload = Payload::from([(
+        PayloadKey::Destination.to_string(),
+        PayloadType::Pubkey(Keypair::new().pubkey()),
+    )]);
+
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::PubkeySortedListMatchCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with a wallet in the list.
+    let payload = Payload::from([(
+        PayloadKey::Destination.to_string(),
+        PayloadType::Pubkey(allowed_wallet),
+    )]);
+
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_program_owned_sorted_list() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a sorted allow-list of programs including lpl-token-auth-rules.
+    let mut programs = (0..50)
+        .map(|_| Keypair::new().pubkey())
+        .collect::<Vec<Pubkey>>();
+    programs.push(lpl_token_auth_rules::ID);
+    programs.sort();
+
+    // Create a Rule.
+    let rule = Rule::ProgramOwnedSortedList {
+        programs,
+        field: PayloadKey::Destination.to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a mint, which is owned by a program NOT in the list.
+    let mint = Keypair::new();
+    let payer_pubkey = context.payer.pubkey();
+    create_mint(&mut context, &mint, &payer_pubkey, None, 0)
+        .await
+        .unwrap();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with the mint.
+    let payload = Payload::from([(
+        PayloadKey::Destination.to_string(),
+        PayloadType::Pubkey(mint.pubkey()),
+    )]);
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        payload,
+        vec![AccountMeta::new_readonly(mint.pubkey(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::ProgramOwnedSortedListCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with the RuleSet, which
+    // is owned by lpl-token-auth-rules.
+    let payload = Payload::from([(
+        PayloadKey::Destination.to_string(),
+        PayloadType::Pubkey(rule_set_addr),
+    )]);
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        payload,
+        vec![AccountMeta::new_readonly(rule_set_addr, false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn create_unsorted_list_fails() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a RuleSet with a sorted list rule whose list is in descending order.
+    let mut pubkeys = (0..3)
+        .map(|_| Keypair::new().pubkey())
+        .collect::<Vec<Pubkey>>();
+    pubkeys.sort();
+    pubkeys.reverse();
+
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            Rule::Not {
+                rule: Box::new(Rule::PubkeySortedListMatch {
+                    pubkeys,
+                    field: PayloadKey::Destination.to_string(),
+                }),
+            },
+        )
+        .unwrap();
+
+    // --------------------------------
+    // Fail on-chain creation
+    // --------------------------------
+    let err = try_create_rule_set(&mut context, rule_set, "test rule_set".to_string())
+        .await
+        .expect_err("Creation should fail");
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::ListNotSorted);
+}
@@ -0,0 +1,152 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{CompareOp, Rule, RuleSetV1},
+};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use utils::{create_validate_ix, program_test, try_create_rule_set, Operation, PayloadKey};
+
+#[tokio::test]
+async fn test_threshold() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create some rules.
+    let adtl_signer = Rule::AdditionalSigner {
+        account: Keypair::new().pubkey(),
+    };
+
+    let amount_check = Rule::Amount {
+        amount: 5,
+        operator: CompareOp::Lt,
+        field: PayloadKey::Amount.to_string(),
+    };
+
+    let destination = Keypair::new().pubkey();
+    let destination_check = Rule::PubkeyMatch {
+        pubkey: destination,
+        field: PayloadKey::Destination.to_string(),
+    };
+
+    let overall_rule = Rule::Threshold {
+        required: 2,
+        rules: vec![adtl_signer, amount_check, destination_check],
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            overall_rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store a payload of data with the WRONG amount but the correct destination.
+    let payload = Payload::from([
+        (PayloadKey::Amount.to_string(), PayloadType::Number(5)),
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(destination),
+        ),
+    ]);
+
+    // Create a `validate` instruction without the additional signer and sending WRONG amount.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.  Only one Rule passed and at least one Rule failed.
+    assert_custom_error!(err, RuleSetError::ThresholdCheckFailed);
+
+    // --------------------------------
+    // Validate error
+    // --------------------------------
+    // Store a payload of data with the correct amount but no destination.
+    let payload = Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(4))]);
+
+    // Create a `validate` instruction without the additional signer and sending no destination.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.  No Rule failed, so the last error rolls up.
+    assert_custom_error!(err, RuleSetError::MissingPayloadValue);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store a payload of data with the correct amount and destination.
+    let payload = Payload::from([
+        (PayloadKey::Amount.to_string(), PayloadType::Number(4)),
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(destination),
+        ),
+    ]);
+
+    // Create a `validate` instruction without the additional signer.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Validate Transfer operation since two of the three Rule conditions were true.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn create_threshold_unreachable_fails() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Threshold requiring more rules than it contains.
+    let overall_rule = Rule::Threshold {
+        required: 3,
+        rules: vec![Rule::Pass, Rule::Pass],
+    };
+
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            overall_rule,
+        )
+        .unwrap();
+
+    // --------------------------------
+    // Fail on-chain creation
+    // --------------------------------
+    let err = try_create_rule_set(&mut context, rule_set, "test rule_set".to_string())
+        .await
+        .expect_err("Creation should fail");
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::ThresholdUnreachable);
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::Payload,
+    state::{ClockValue, Rule, RuleSetV1},
+};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use utils::{create_validate_ix, program_test, Operation};
+
+#[tokio::test]
+async fn test_time_window_open_start() {
+    parametric_time_window_check(None, Some(i64::MAX), ClockValue::UnixTimestamp, true).await;
+}
+
+#[tokio::test]
+async fn test_time_window_open_end() {
+    parametric_time_window_check(Some(0), None, ClockValue::UnixTimestamp, true).await;
+}
+
+#[tokio::test]
+async fn time_window_fail_not_started() {
+    parametric_time_window_check(Some(i64::MAX), None, ClockValue::UnixTimestamp, false).await;
+}
+
+#[tokio::test]
+async fn time_window_fail_ended() {
+    parametric_time_window_check(None, Some(0), ClockValue::UnixTimestamp, false).await;
+}
+
+#[tokio::test]
+async fn test_time_window_slot() {
+    parametric_time_window_check(Some(0), Some(1_000_000), ClockValue::Slot, true).await;
+}
+
+#[tokio::test]
+async fn time_window_slot_fail_not_started() {
+    parametric_time_window_check(Some(1_000_000), None, ClockValue::Slot, false).await;
+}
+
+async fn parametric_time_window_check(
+    start: Option<i64>,
+    end: Option<i64>,
+    clock_value: ClockValue,
+    should_pass: bool,
+) {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule.
+    let rule = Rule::TimeWindow {
+        start,
+        end,
+        clock_value,
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // --------------------------------
+    // Validate
+    // --------------------------------
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(rule_set_addr, mint, Payload::default(), vec![], None);
+
+    if should_pass {
+        // Validate Transfer operation.
+        process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+    } else {
+        // Fail to validate Transfer operation.
+        let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+        // Check that error is what we expect.
+        assert_custom_error!(err, RuleSetError::TimeWindowCheckFailed);
+    }
+}
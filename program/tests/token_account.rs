@@ -0,0 +1,301 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{AmountTarget, CompareOp, PubkeyTarget, Rule, RuleSetV1, TokenAccountField},
+};
+use solana_program_test::tokio;
+use solana_sdk::{instruction::AccountMeta, signature::Signer, signer::keypair::Keypair};
+use utils::{
+    create_associated_token_account, create_mint, create_validate_ix, mint_to, program_test,
+    Operation, PayloadKey,
+};
+
+#[tokio::test]
+async fn test_token_account_owner_match() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Wallets allowed to receive the token.
+    let allowed_wallet = Keypair::new();
+
+    // Create a Rule.
+    let rule = Rule::TokenAccountMatch {
+        field: PayloadKey::Destination.to_string(),
+        token_account_field: TokenAccountField::Owner,
+        target: PubkeyTarget::List(vec![Keypair::new().pubkey(), allowed_wallet.pubkey()]),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a mint and token accounts for the allowed wallet and another wallet.
+    let mint = Keypair::new();
+    let payer_pubkey = context.payer.pubkey();
+    create_mint(&mut context, &mint, &payer_pubkey, None, 0)
+        .await
+        .unwrap();
+
+    let other_wallet = Keypair::new();
+    let other_token_account =
+        create_associated_token_account(&mut context, &other_wallet, &mint.pubkey())
+            .await
+            .unwrap();
+
+    let allowed_token_account =
+        create_associated_token_account(&mut context, &allowed_wallet, &mint.pubkey())
+            .await
+            .unwrap();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with a token account
+    // owned by a wallet NOT in the list.
+    let payload = Payload::from([(
+        PayloadKey::Destination.to_string(),
+        PayloadType::Pubkey(other_token_account),
+    )]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        payload,
+        vec![AccountMeta::new_readonly(other_token_account, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::TokenAccountMatchCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with a token account
+    // owned by a wallet in the list.
+    let payload = Payload::from([(
+        PayloadKey::Destination.to_string(),
+        PayloadType::Pubkey(allowed_token_account),
+    )]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        payload,
+        vec![AccountMeta::new_readonly(allowed_token_account, false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_token_account_mint_match() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule.
+    let rule = Rule::TokenAccountMatch {
+        field: PayloadKey::Source.to_string(),
+        token_account_field: TokenAccountField::Mint,
+        target: PubkeyTarget::Mint,
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a mint and a token account.
+    let mint = Keypair::new();
+    let payer_pubkey = context.payer.pubkey();
+    create_mint(&mut context, &mint, &payer_pubkey, None, 0)
+        .await
+        .unwrap();
+
+    let wallet = Keypair::new();
+    let token_account = create_associated_token_account(&mut context, &wallet, &mint.pubkey())
+        .await
+        .unwrap();
+
+    let payload = Payload::from([(
+        PayloadKey::Source.to_string(),
+        PayloadType::Pubkey(token_account),
+    )]);
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Create a `validate` instruction with a DIFFERENT mint.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        Keypair::new().pubkey(),
+        payload.clone(),
+        vec![AccountMeta::new_readonly(token_account, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::TokenAccountMatchCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Create a `validate` instruction with the token account's mint.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        payload,
+        vec![AccountMeta::new_readonly(token_account, false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_token_account_amount() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule.
+    let rule = Rule::TokenAccountAmount {
+        field: PayloadKey::Source.to_string(),
+        operator: CompareOp::GtEq,
+        target: AmountTarget::Field(PayloadKey::Amount.to_string()),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a mint and a token account holding 10 tokens.
+    let mint = Keypair::new();
+    let manager = Keypair::new();
+    create_mint(&mut context, &mint, &manager.pubkey(), None, 0)
+        .await
+        .unwrap();
+
+    let wallet = Keypair::new();
+    let token_account = create_associated_token_account(&mut context, &wallet, &mint.pubkey())
+        .await
+        .unwrap();
+
+    mint_to(&mut context, &mint.pubkey(), &token_account, &manager, 10)
+        .await
+        .unwrap();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with an amount greater
+    // than the balance.
+    let payload = Payload::from([
+        (
+            PayloadKey::Source.to_string(),
+            PayloadType::Pubkey(token_account),
+        ),
+        (PayloadKey::Amount.to_string(), PayloadType::Number(20)),
+    ]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        payload,
+        vec![AccountMeta::new_readonly(token_account, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::TokenAccountAmountCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with an amount covered
+    // by the balance.
+    let payload = Payload::from([
+        (
+            PayloadKey::Source.to_string(),
+            PayloadType::Pubkey(token_account),
+        ),
+        (PayloadKey::Amount.to_string(), PayloadType::Number(5)),
+    ]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        payload,
+        vec![AccountMeta::new_readonly(token_account, false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
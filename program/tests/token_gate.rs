@@ -0,0 +1,284 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{PubkeyTarget, Rule, RuleSetV1},
+};
+use solana_program_test::tokio;
+use solana_sdk::{instruction::AccountMeta, signature::Signer, signer::keypair::Keypair};
+use utils::{
+    create_associated_token_account, create_mint, create_test_merkle_tree_from_one_leaf,
+    create_validate_ix, mint_to, program_test, Operation, PayloadKey,
+};
+
+#[tokio::test]
+async fn test_token_gate() {
+    let mut context = program_test().start_with_context().await;
+
+    // Create a membership mint.
+    let membership_mint = Keypair::new();
+    let manager = Keypair::new();
+    create_mint(&mut context, &membership_mint, &manager.pubkey(), None, 0)
+        .await
+        .unwrap();
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule.
+    let rule = Rule::TokenGate {
+        wallet_field: PayloadKey::Authority.to_string(),
+        token_account_field: PayloadKey::Holder.to_string(),
+        mint: PubkeyTarget::Pubkey(membership_mint.pubkey()),
+        amount: 1,
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a member token account that does not hold any tokens yet.
+    let member = Keypair::new();
+    let member_token_account =
+        create_associated_token_account(&mut context, &member, &membership_mint.pubkey())
+            .await
+            .unwrap();
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let payload = Payload::from([
+        (
+            PayloadKey::Authority.to_string(),
+            PayloadType::Pubkey(member.pubkey()),
+        ),
+        (
+            PayloadKey::Holder.to_string(),
+            PayloadType::Pubkey(member_token_account),
+        ),
+    ]);
+
+    // --------------------------------
+    // Validate fail empty balance
+    // --------------------------------
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload.clone(),
+        vec![AccountMeta::new_readonly(member_token_account, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::TokenGateCheckFailed);
+
+    // --------------------------------
+    // Validate fail wrong wallet
+    // --------------------------------
+    mint_to(
+        &mut context,
+        &membership_mint.pubkey(),
+        &member_token_account,
+        &manager,
+        1,
+    )
+    .await
+    .unwrap();
+
+    // Store the payload of data to validate against the rule definition with a wallet that does
+    // not hold the token account.
+    let wrong_wallet_payload = Payload::from([
+        (
+            PayloadKey::Authority.to_string(),
+            PayloadType::Pubkey(Keypair::new().pubkey()),
+        ),
+        (
+            PayloadKey::Holder.to_string(),
+            PayloadType::Pubkey(member_token_account),
+        ),
+    ]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        wrong_wallet_payload,
+        vec![AccountMeta::new_readonly(member_token_account, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::TokenGateCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload,
+        vec![AccountMeta::new_readonly(member_token_account, false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_token_gate_tree() {
+    let mut context = program_test().start_with_context().await;
+
+    // Create a membership mint that is a member of a collection Merkle tree.
+    let membership_mint = Keypair::new();
+    let manager = Keypair::new();
+    create_mint(&mut context, &membership_mint, &manager.pubkey(), None, 0)
+        .await
+        .unwrap();
+
+    let tree = create_test_merkle_tree_from_one_leaf(&membership_mint.pubkey(), 4);
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule.
+    let rule = Rule::TokenGate {
+        wallet_field: PayloadKey::Authority.to_string(),
+        token_account_field: PayloadKey::Holder.to_string(),
+        mint: PubkeyTarget::Tree {
+            root: tree.root,
+            proof_field: PayloadKey::AuthorityProof.to_string(),
+        },
+        amount: 1,
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a member token account holding one token.
+    let member = Keypair::new();
+    let member_token_account =
+        create_associated_token_account(&mut context, &member, &membership_mint.pubkey())
+            .await
+            .unwrap();
+
+    mint_to(
+        &mut context,
+        &membership_mint.pubkey(),
+        &member_token_account,
+        &manager,
+        1,
+    )
+    .await
+    .unwrap();
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with an INCORRECT proof.
+    let incorrect_tree = create_test_merkle_tree_from_one_leaf(&membership_mint.pubkey(), 4);
+
+    let payload = Payload::from([
+        (
+            PayloadKey::Authority.to_string(),
+            PayloadType::Pubkey(member.pubkey()),
+        ),
+        (
+            PayloadKey::Holder.to_string(),
+            PayloadType::Pubkey(member_token_account),
+        ),
+        (
+            PayloadKey::AuthorityProof.to_string(),
+            PayloadType::MerkleProof(incorrect_tree.proof),
+        ),
+    ]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload,
+        vec![AccountMeta::new_readonly(member_token_account, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::TokenGateCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with the CORRECT proof.
+    let payload = Payload::from([
+        (
+            PayloadKey::Authority.to_string(),
+            PayloadType::Pubkey(member.pubkey()),
+        ),
+        (
+            PayloadKey::Holder.to_string(),
+            PayloadType::Pubkey(member_token_account),
+        ),
+        (
+            PayloadKey::AuthorityProof.to_string(),
+            PayloadType::MerkleProof(tree.proof),
+        ),
+    ]);
+
+    // Create a `validate` instruction.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        payload,
+        vec![AccountMeta::new_readonly(member_token_account, false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
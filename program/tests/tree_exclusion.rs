@@ -0,0 +1,252 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    state::{Rule, RuleSetV1},
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::tokio;
+use solana_sdk::{instruction::AccountMeta, signature::Signer, signer::keypair::Keypair};
+use utils::{create_mint, create_validate_ix, program_test, ExclusionTree, Operation, PayloadKey};
+
+#[tokio::test]
+async fn test_pubkey_tree_exclusion() {
+    let mut context = program_test().start_with_context().await;
+
+    // Create a deny-list of wallets.
+    let denied_wallets = (0..5)
+        .map(|_| Keypair::new().pubkey())
+        .collect::<Vec<Pubkey>>();
+    let tree = ExclusionTree::new(denied_wallets.clone());
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule.
+    let rule = Rule::PubkeyTreeExclusion {
+        root: tree.root,
+        pubkey_field: PayloadKey::Destination.to_string(),
+        proof_field: PayloadKey::DestinationProof.to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    // --------------------------------
+    // Validate fail missing proof
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition WITHOUT a proof.
+    let allowed_wallet = Keypair::new().pubkey();
+    let payload = Payload::from([(
+        PayloadKey::Destination.to_string(),
+        PayloadType::Pubkey(allowed_wallet),
+    )]);
+
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::MissingPayloadValue);
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with a denied wallet.
+    let denied_wallet = denied_wallets[2];
+    let payload = Payload::from([
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(denied_wallet),
+        ),
+        (
+            PayloadKey::DestinationProof.to_string(),
+            PayloadType::ExclusionProof(tree.exclusion_proof(&denied_wallet)),
+        ),
+    ]);
+
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::PubkeyTreeExclusionCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with a wallet not in the
+    // deny-list.
+    let payload = Payload::from([
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(allowed_wallet),
+        ),
+        (
+            PayloadKey::DestinationProof.to_string(),
+            PayloadType::ExclusionProof(tree.exclusion_proof(&allowed_wallet)),
+        ),
+    ]);
+
+    let validate_ix = create_validate_ix(rule_set_addr, mint, payload, vec![], None);
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
+
+#[tokio::test]
+async fn test_program_owned_tree_exclusion() {
+    let mut context = program_test().start_with_context().await;
+
+    // Create a deny-list of programs including the token program.
+    let mut denied_programs = (0..5)
+        .map(|_| Keypair::new().pubkey())
+        .collect::<Vec<Pubkey>>();
+    denied_programs.push(safe_token::ID);
+    let tree = ExclusionTree::new(denied_programs);
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule.
+    let rule = Rule::ProgramOwnedTreeExclusion {
+        root: tree.root,
+        pubkey_field: PayloadKey::Destination.to_string(),
+        proof_field: PayloadKey::DestinationProof.to_string(),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a mint, which is owned by the token program.
+    let mint = Keypair::new();
+    let payer_pubkey = context.payer.pubkey();
+    create_mint(&mut context, &mint, &payer_pubkey, None, 0)
+        .await
+        .unwrap();
+
+    // --------------------------------
+    // Validate fail uninitialized account
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with an uninitialized
+    // wallet, which is owned by the System Program and could be assigned to a denied program.
+    let wallet = Keypair::new().pubkey();
+    let payload = Payload::from([
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(wallet),
+        ),
+        (
+            PayloadKey::DestinationProof.to_string(),
+            PayloadType::ExclusionProof(tree.exclusion_proof(&solana_program::system_program::ID)),
+        ),
+    ]);
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        payload,
+        vec![AccountMeta::new_readonly(wallet, false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::DataIsEmpty);
+
+    // --------------------------------
+    // Validate fail
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with the mint.
+    let payload = Payload::from([
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(mint.pubkey()),
+        ),
+        (
+            PayloadKey::DestinationProof.to_string(),
+            PayloadType::ExclusionProof(tree.exclusion_proof(&safe_token::ID)),
+        ),
+    ]);
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        payload,
+        vec![AccountMeta::new_readonly(mint.pubkey(), false)],
+        None,
+    );
+
+    // Fail to validate Transfer operation.
+    let err = process_failing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::ProgramOwnedTreeExclusionCheckFailed);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    // Store the payload of data to validate against the rule definition with the RuleSet, which
+    // is owned by lpl-token-auth-rules.
+    let payload = Payload::from([
+        (
+            PayloadKey::Destination.to_string(),
+            PayloadType::Pubkey(rule_set_addr),
+        ),
+        (
+            PayloadKey::DestinationProof.to_string(),
+            PayloadType::ExclusionProof(tree.exclusion_proof(&lpl_token_auth_rules::ID)),
+        ),
+    ]);
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint.pubkey(),
+        payload,
+        vec![AccountMeta::new_readonly(rule_set_addr, false)],
+        None,
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![], None).await;
+}
@@ -0,0 +1,440 @@
+#![cfg(feature = "test-bpf")]
+
+pub mod utils;
+
+use borsh::BorshDeserialize;
+use lpl_token_auth_rules::{
+    error::RuleSetError,
+    payload::{Payload, PayloadType},
+    pda::find_rule_set_state_address,
+    state::{Key, Rule, RuleSetStateAccount, RuleSetV1, RuleState},
+};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+use utils::{
+    advance_clock, create_validate_ix, program_test, Operation, PayloadKey, RuleStateAccounts,
+};
+
+#[tokio::test]
+async fn test_usage_limit() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing two uses, combined with a quota so both store their state in the
+    // same state PDA.
+    let rule_authority = Keypair::new();
+    let usage_limit = Rule::UsageLimit {
+        name: "uses".to_string(),
+        authority: rule_authority.pubkey(),
+        limit: 2,
+        period: None,
+    };
+    let quota = Rule::Quota {
+        name: "daily".to_string(),
+        authority: rule_authority.pubkey(),
+        field: PayloadKey::Amount.to_string(),
+        cap: 10,
+        period: 24 * 60 * 60,
+    };
+
+    let rule = Rule::All {
+        rules: vec![usage_limit.clone(), quota.clone()],
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            rule,
+        )
+        .unwrap();
+
+    println!("{:#?}", rule_set);
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    // --------------------------------
+    // Validate pass twice
+    // --------------------------------
+    for amount in [3, 4] {
+        let validate_ix = create_validate_ix(
+            rule_set_addr,
+            mint,
+            Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(amount))]),
+            vec![],
+            Some(RuleStateAccounts {
+                payer: context.payer.pubkey(),
+                rule_authority: rule_authority.pubkey(),
+                rule_set_state_pda: rule_set_state_addr,
+                update_rule_state: true,
+            }),
+        );
+
+        // Validate Transfer operation.
+        process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+    }
+
+    // Check that both rules stored their state.
+    let state_account = get_rule_set_state(&mut context, rule_set_state_addr).await;
+    let operation = Operation::Transfer {
+        scenario: utils::TransferScenario::Holder,
+    }
+    .to_string();
+
+    match state_account
+        .states
+        .get(&RuleSetStateAccount::state_key(&usage_limit, &operation).unwrap())
+    {
+        Some(RuleState::UsageLimit { count, .. }) => assert_eq!(*count, 2),
+        _ => panic!("Missing UsageLimit state"),
+    }
+
+    match state_account
+        .states
+        .get(&RuleSetStateAccount::state_key(&quota, &operation).unwrap())
+    {
+        Some(RuleState::Quota { amount, .. }) => assert_eq!(*amount, 7),
+        _ => panic!("Missing Quota state"),
+    }
+
+    // --------------------------------
+    // Validate fail over limit
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(1))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::UsageLimitCheckFailed);
+
+    // --------------------------------
+    // Validate fail without updating state
+    // --------------------------------
+    // The limit is still checked when state is not being updated.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(1))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: false,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::UsageLimitCheckFailed);
+
+    // --------------------------------
+    // Validate pass for another mint
+    // --------------------------------
+    // The count is kept per mint.
+    let other_mint = Keypair::new().pubkey();
+    let (other_rule_set_state_addr, _rule_set_bump) = find_rule_set_state_address(
+        context.payer.pubkey(),
+        "test rule_set".to_string(),
+        other_mint,
+    );
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        other_mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(1))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: other_rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+}
+
+#[tokio::test]
+async fn test_usage_limit_period() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing one use per hour.
+    let rule_authority = Keypair::new();
+    let usage_limit = Rule::UsageLimit {
+        name: "uses".to_string(),
+        authority: rule_authority.pubkey(),
+        limit: 1,
+        period: Some(60 * 60),
+    };
+
+    // Create a RuleSet.
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            Operation::Transfer {
+                scenario: utils::TransferScenario::Holder,
+            }
+            .to_string(),
+            usage_limit,
+        )
+        .unwrap();
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    // --------------------------------
+    // Validate pass
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(1))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // --------------------------------
+    // Validate fail over limit
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(2))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::UsageLimitCheckFailed);
+
+    // --------------------------------
+    // Validate pass after period
+    // --------------------------------
+    // Move the clock past the period, which resets the count.
+    advance_clock(&mut context, 60 * 60).await;
+
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(3))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+}
+
+#[tokio::test]
+async fn test_usage_limit_kept_after_update() {
+    let mut context = program_test().start_with_context().await;
+
+    // --------------------------------
+    // Create RuleSet
+    // --------------------------------
+    // Create a Rule allowing two uses.
+    let rule_authority = Keypair::new();
+    let operation = Operation::Transfer {
+        scenario: utils::TransferScenario::Holder,
+    }
+    .to_string();
+
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(
+            operation.clone(),
+            Rule::UsageLimit {
+                name: "uses".to_string(),
+                authority: rule_authority.pubkey(),
+                limit: 2,
+                period: None,
+            },
+        )
+        .unwrap();
+
+    // Put the RuleSet on chain.
+    let rule_set_addr =
+        create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // Create a Keypair to simulate a token mint address.
+    let mint = Keypair::new().pubkey();
+
+    let (rule_set_state_addr, _rule_set_bump) =
+        find_rule_set_state_address(context.payer.pubkey(), "test rule_set".to_string(), mint);
+
+    // --------------------------------
+    // Validate pass twice
+    // --------------------------------
+    for amount in [1, 2] {
+        let validate_ix = create_validate_ix(
+            rule_set_addr,
+            mint,
+            Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(amount))]),
+            vec![],
+            Some(RuleStateAccounts {
+                payer: context.payer.pubkey(),
+                rule_authority: rule_authority.pubkey(),
+                rule_set_state_pda: rule_set_state_addr,
+                update_rule_state: true,
+            }),
+        );
+
+        // Validate Transfer operation.
+        process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+    }
+
+    // --------------------------------
+    // Update RuleSet
+    // --------------------------------
+    // Raise the limit to three uses.
+    let usage_limit = Rule::UsageLimit {
+        name: "uses".to_string(),
+        authority: rule_authority.pubkey(),
+        limit: 3,
+        period: None,
+    };
+
+    let mut rule_set = RuleSetV1::new("test rule_set".to_string(), context.payer.pubkey());
+    rule_set
+        .add(operation.clone(), usage_limit.clone())
+        .unwrap();
+
+    // Put the updated RuleSet on chain.
+    create_rule_set_on_chain!(&mut context, rule_set, "test rule_set".to_string()).await;
+
+    // --------------------------------
+    // Validate pass once more
+    // --------------------------------
+    // The two previous uses still count towards the new limit.
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(3))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Validate Transfer operation.
+    process_passing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    let state_account = get_rule_set_state(&mut context, rule_set_state_addr).await;
+    match state_account
+        .states
+        .get(&RuleSetStateAccount::state_key(&usage_limit, &operation).unwrap())
+    {
+        Some(RuleState::UsageLimit { count, .. }) => assert_eq!(*count, 3),
+        _ => panic!("Missing UsageLimit state"),
+    }
+
+    // --------------------------------
+    // Validate fail over new limit
+    // --------------------------------
+    let validate_ix = create_validate_ix(
+        rule_set_addr,
+        mint,
+        Payload::from([(PayloadKey::Amount.to_string(), PayloadType::Number(4))]),
+        vec![],
+        Some(RuleStateAccounts {
+            payer: context.payer.pubkey(),
+            rule_authority: rule_authority.pubkey(),
+            rule_set_state_pda: rule_set_state_addr,
+            update_rule_state: true,
+        }),
+    );
+
+    // Fail to validate Transfer operation.
+    let err =
+        process_failing_validate_ix!(&mut context, validate_ix, vec![&rule_authority], None).await;
+
+    // Check that error is what we expect.
+    assert_custom_error!(err, RuleSetError::UsageLimitCheckFailed);
+}
+
+async fn get_rule_set_state(
+    context: &mut ProgramTestContext,
+    rule_set_state_addr: Pubkey,
+) -> RuleSetStateAccount {
+    let rule_set_state_account = context
+        .banks_client
+        .get_account(rule_set_state_addr)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(rule_set_state_account.owner, lpl_token_auth_rules::ID);
+
+    let state_account = RuleSetStateAccount::try_from_slice(&rule_set_state_account.data).unwrap();
+    assert_eq!(state_account.key, Key::RuleSetState);
+
+    state_account
+}
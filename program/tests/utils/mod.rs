@@ -1,14 +1,19 @@
 use lpl_token_auth_rules::{
     instruction::{
-        builders::{CreateOrUpdateBuilder, PuffRuleSetBuilder, WriteToBufferBuilder},
-        CreateOrUpdateArgs, InstructionBuilder, PuffRuleSetArgs, WriteToBufferArgs,
+        builders::{
+            CreateOrUpdateBuilder, PuffRuleSetBuilder, ValidateBuilder, WriteToBufferBuilder,
+        },
+        CreateOrUpdateArgs, InstructionBuilder, PuffRuleSetArgs, ValidateArgs, WriteToBufferArgs,
     },
-    payload::ProofInfo,
-    state::RuleSetV1,
+    payload::{ExclusionProofInfo, Payload, ProofInfo},
 };
 use rmp_serde::Serializer;
 use serde::Serialize;
-use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
 use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
 use solana_sdk::{
     compute_budget::ComputeBudgetInstruction, program_pack::Pack, signature::Signer,
@@ -169,6 +174,8 @@ pub enum PayloadKey {
     /// Merkle proof for the destination of the operation, e.g. when the distination
     /// is a member of a Merkle tree.
     DestinationProof,
+    /// A single-use nonce for the operation.
+    Nonce,
 }
 
 impl ToString for PayloadKey {
@@ -184,6 +191,7 @@ impl ToString for PayloadKey {
             PayloadKey::Destination => "Destination",
             PayloadKey::DestinationSeeds => "DestinationSeeds",
             PayloadKey::DestinationProof => "DestinationProof",
+            PayloadKey::Nonce => "Nonce",
             PayloadKey::Holder => "Holder",
             PayloadKey::Source => "Source",
             PayloadKey::SourceSeeds => "SourceSeeds",
@@ -212,7 +220,7 @@ macro_rules! create_rule_set_on_chain {
 
 pub async fn create_rule_set_on_chain_with_loc(
     context: &mut ProgramTestContext,
-    rule_set: RuleSetV1,
+    rule_set: impl Serialize,
     rule_set_name: String,
     file: &str,
     line: u32,
@@ -266,6 +274,103 @@ pub async fn create_rule_set_on_chain_with_loc(
     rule_set_addr
 }
 
+// Create a `RuleSet` on chain, returning the result so that tests can check a failed creation.
+pub async fn try_create_rule_set(
+    context: &mut ProgramTestContext,
+    rule_set: impl Serialize,
+    rule_set_name: String,
+) -> Result<(), BanksClientError> {
+    // Find RuleSet PDA.
+    let (rule_set_addr, _rule_set_bump) =
+        lpl_token_auth_rules::pda::find_rule_set_address(context.payer.pubkey(), rule_set_name);
+
+    // Serialize the RuleSet using RMP serde.
+    let mut serialized_rule_set = Vec::new();
+    rule_set
+        .serialize(&mut Serializer::new(&mut serialized_rule_set))
+        .unwrap();
+
+    // Create a `create_or_update` instruction.
+    let create_ix = CreateOrUpdateBuilder::new()
+        .payer(context.payer.pubkey())
+        .rule_set_pda(rule_set_addr)
+        .build(CreateOrUpdateArgs::V1 {
+            serialized_rule_set,
+        })
+        .unwrap()
+        .instruction();
+
+    // Add it to a transaction.
+    let create_tx = Transaction::new_signed_with_payer(
+        &[create_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    // Process the transaction.
+    context.banks_client.process_transaction(create_tx).await
+}
+
+// The accounts passed to `validate` for `Rule`s that keep state in the `RuleSet` state PDA.
+pub struct RuleStateAccounts {
+    pub payer: Pubkey,
+    pub rule_authority: Pubkey,
+    pub rule_set_state_pda: Pubkey,
+    pub update_rule_state: bool,
+}
+
+// Create a `validate` instruction for the Holder Transfer operation.
+pub fn create_validate_ix(
+    rule_set_addr: Pubkey,
+    mint: Pubkey,
+    payload: Payload,
+    additional_rule_accounts: Vec<AccountMeta>,
+    rule_state_accounts: Option<RuleStateAccounts>,
+) -> Instruction {
+    let mut builder = ValidateBuilder::new();
+    builder
+        .rule_set_pda(rule_set_addr)
+        .mint(mint)
+        .additional_rule_accounts(additional_rule_accounts);
+
+    let mut update_rule_state = false;
+    if let Some(rule_state_accounts) = rule_state_accounts {
+        builder
+            .payer(rule_state_accounts.payer)
+            .rule_authority(rule_state_accounts.rule_authority)
+            .rule_set_state_pda(rule_state_accounts.rule_set_state_pda);
+        update_rule_state = rule_state_accounts.update_rule_state;
+    }
+
+    builder
+        .build(ValidateArgs::V1 {
+            operation: Operation::Transfer {
+                scenario: TransferScenario::Holder,
+            }
+            .to_string(),
+            payload,
+            update_rule_state,
+            rule_set_revision: None,
+        })
+        .unwrap()
+        .instruction()
+}
+
+// Move the Clock forward by a number of seconds.  The slot is moved forward as well, and the new
+// blockhash used, to avoid duplicate transactions.
+pub async fn advance_clock(context: &mut ProgramTestContext, seconds: i64) {
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    context.warp_to_slot(clock.slot + 1).unwrap();
+    context.last_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+
+    context.set_sysvar(&Clock {
+        slot: clock.slot + 1,
+        unix_timestamp: clock.unix_timestamp + seconds,
+        ..clock
+    });
+}
+
 #[macro_export]
 macro_rules! create_big_rule_set_on_chain {
     ($context:expr, $rule_set:expr, $rule_set_name:expr, $compute_budget:expr) => {
@@ -283,7 +388,7 @@ macro_rules! create_big_rule_set_on_chain {
 
 pub async fn create_big_rule_set_on_chain_with_loc(
     context: &mut ProgramTestContext,
-    rule_set: RuleSetV1,
+    rule_set: impl Serialize,
     rule_set_name: String,
     compute_budget: Option<u32>,
     file: &str,
@@ -569,6 +674,37 @@ macro_rules! assert_custom_error {
     };
 }
 
+#[macro_export]
+macro_rules! assert_owner_error {
+    ($error:expr, $code:expr) => {
+        let calling_location = format!(
+            "assert_owner_error called at {}:{}:{}",
+            file!(),
+            line!(),
+            column!()
+        );
+
+        match $error {
+            solana_program_test::BanksClientError::TransactionError(
+                solana_sdk::transaction::TransactionError::InstructionError(
+                    0,
+                    solana_program::instruction::InstructionError::Custom(x),
+                ),
+            ) => assert_eq!(
+                x,
+                lpl_token_auth_rules::error::OWNER_ERROR_CODE_OFFSET + $code as u32,
+                "Expected another owner error code, {}",
+                calling_location
+            ),
+            err => assert!(
+                false,
+                "Expected custom instruction error but got '{:#?}', {}",
+                err, calling_location
+            ),
+        };
+    };
+}
+
 pub async fn create_mint(
     context: &mut ProgramTestContext,
     mint: &Keypair,
@@ -634,6 +770,31 @@ pub async fn create_associated_token_account(
     ))
 }
 
+pub async fn mint_to(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    account: &Pubkey,
+    manager: &Keypair,
+    amount: u64,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[safe_token::instruction::mint_to(
+            &safe_token::id(),
+            mint,
+            account,
+            &manager.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap()],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, manager],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
 pub fn cmp_slice<T: PartialEq>(a: &[T], b: &[T]) -> bool {
     let matching = a.iter().zip(b.iter()).filter(|&(a, b)| a == b).count();
     matching == a.len() && matching == b.len()
@@ -671,3 +832,68 @@ pub fn create_test_merkle_tree_from_one_leaf(leaf: &Pubkey, levels: usize) -> Me
         proof: ProofInfo::new(proof),
     }
 }
+
+pub struct ExclusionTree {
+    pub root: [u8; 32],
+    leaves: Vec<Pubkey>,
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl ExclusionTree {
+    // Build a sorted Merkle tree from the leaves, bounded by the all-zero and all-one `Pubkey`s
+    // and padded with the all-one `Pubkey` to a power of two.
+    pub fn new(mut leaves: Vec<Pubkey>) -> Self {
+        leaves.push(Pubkey::new_from_array([0x00; 32]));
+        leaves.push(Pubkey::new_from_array([0xff; 32]));
+        leaves.sort();
+        leaves.dedup();
+
+        while !leaves.len().is_power_of_two() {
+            leaves.push(Pubkey::new_from_array([0xff; 32]));
+        }
+
+        let mut levels = vec![leaves
+            .iter()
+            .map(|leaf| solana_program::keccak::hashv(&[&[0x00], leaf.as_ref()]).0)
+            .collect::<Vec<[u8; 32]>>()];
+
+        while levels.last().unwrap().len() > 1 {
+            let next_level = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| solana_program::keccak::hashv(&[&[0x01], &pair[0], &pair[1]]).0)
+                .collect();
+
+            levels.push(next_level);
+        }
+
+        Self {
+            root: levels.last().unwrap()[0],
+            leaves,
+            levels,
+        }
+    }
+
+    // Get the proof for the leaf at the index.
+    fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+        self.levels[..self.levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(level, hashes)| hashes[(index >> level) ^ 1])
+            .collect()
+    }
+
+    // Get the proof that the key falls between the last leaf less than it and the next leaf.
+    pub fn exclusion_proof(&self, key: &Pubkey) -> ExclusionProofInfo {
+        let index = self.leaves.partition_point(|leaf| leaf < key) - 1;
+
+        ExclusionProofInfo::new(
+            index as u32,
+            self.leaves[index],
+            self.proof(index),
+            self.leaves[index + 1],
+            self.proof(index + 1),
+        )
+    }
+}